@@ -0,0 +1,50 @@
+#[macro_use]
+extern crate criterion;
+#[macro_use]
+extern crate lazytable;
+
+use std::fmt::Write;
+
+use criterion::Criterion;
+use lazytable::Table;
+
+fn table(rows: usize) -> Table<'static> {
+    let mut table = Table::with_width(60);
+    table.set_title(row!["id", "name", "description", "state"]);
+    for i in 0..rows {
+        table.add_row(row![
+            i.to_string(),
+            format!("item {}", i),
+            "some longer text that needs to be wrapped once or twice",
+            if i % 3 == 0 { "FAILED" } else { "ok" }
+        ]);
+    }
+    table
+}
+
+fn render(c: &mut Criterion) {
+    let table = table(100_000);
+    c.bench_function("render 100k rows", |b| {
+        b.iter(|| {
+            let mut out = String::new();
+            write!(out, "{}", table).unwrap();
+            out
+        })
+    });
+    let mut table = table;
+    table.prepare();
+    c.bench_function("render 100k prepared rows", |b| {
+        b.iter(|| {
+            let mut out = String::new();
+            write!(out, "{}", table).unwrap();
+            out
+        })
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = render
+}
+criterion_main!(benches);
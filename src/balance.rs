@@ -0,0 +1,125 @@
+//! Choosing column widths that give the fewest lines.
+use std::collections::HashMap;
+
+use Wrapping;
+
+/// Line counts of the cells of columns at different widths.
+struct Lines<'r, S: 'r> {
+    rows: &'r [&'r [S]],
+    wrapping: Wrapping,
+    cache: HashMap<(usize, usize), Vec<usize>>,
+}
+
+impl<'r, S: AsRef<str>> Lines<'r, S> {
+    /// The number of lines of each cell of column `col` at `width`.
+    fn column(&mut self, col: usize, width: usize) -> &[usize] {
+        let (rows, wrapping) = (self.rows, self.wrapping);
+        self.cache.entry((col, width)).or_insert_with(|| {
+            rows.iter()
+                .map(|row| {
+                    row.get(col)
+                        .map_or(0, |c| wrapping.split(c.as_ref(), width).len())
+                })
+                .collect()
+        })
+    }
+
+    /// The number of lines of all rows with `widths`.
+    fn height(&mut self, widths: &[usize]) -> usize {
+        let mut heights = vec![1; self.rows.len()];
+        for (col, &width) in widths.iter().enumerate() {
+            for (height, &lines) in heights.iter_mut().zip(self.column(col, width)) {
+                *height = (*height).max(lines);
+            }
+        }
+        heights.iter().sum()
+    }
+
+    /// The smallest widening of column `col` which saves lines of its own,
+    /// if any within `most`.
+    fn next_step(&mut self, col: usize, width: usize, most: usize) -> Option<usize> {
+        let lines: usize = self.column(col, width).iter().sum();
+        (1..=most).find(|&k| self.column(col, width + k).iter().sum::<usize>() < lines)
+    }
+}
+
+/// Column widths with the fewest lines for `rows`, starting from `widths`.
+///
+/// Columns are at most as wide as their `natural` width and at least one
+/// column wide, all together at most `budget` wide. Only widening a column
+/// to a width at which its own cells need fewer lines is considered, so
+/// this finds a good but not necessarily the best layout.
+pub(crate) fn minimize_height<S: AsRef<str>>(
+    rows: &[&[S]],
+    natural: &[usize],
+    mut widths: Vec<usize>,
+    budget: usize,
+    wrapping: Wrapping,
+) -> Vec<usize> {
+    let mut lines = Lines {
+        rows,
+        wrapping,
+        cache: HashMap::new(),
+    };
+    let mut height = lines.height(&widths);
+    loop {
+        let free = budget.saturating_sub(widths.iter().sum());
+        let mut best: Option<(usize, Vec<usize>)> = None;
+        for col in 0..widths.len() {
+            let most = natural[col].saturating_sub(widths[col]);
+            let step = match lines.next_step(col, widths[col], most) {
+                Some(step) => step,
+                None => continue,
+            };
+            let mut candidates = vec![];
+            if step <= free {
+                let mut candidate = widths.clone();
+                candidate[col] += step;
+                candidates.push(candidate);
+            }
+            // Or take the width from another column.
+            for donor in (0..widths.len()).filter(|&d| d != col && widths[d] > step) {
+                let mut candidate = widths.clone();
+                candidate[col] += step;
+                candidate[donor] -= step;
+                candidates.push(candidate);
+            }
+            for candidate in candidates {
+                let candidate_height = lines.height(&candidate);
+                if candidate_height < best.as_ref().map_or(height, |b| b.0) {
+                    best = Some((candidate_height, candidate));
+                }
+            }
+        }
+        match best {
+            Some((best_height, best_widths)) => {
+                height = best_height;
+                widths = best_widths;
+            }
+            None => return widths,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimize_height() {
+        let rows: Vec<&[&str]> = vec![
+            &["a b c d e f", "x"],
+            &["a b c d e f", "x"],
+            &["a", "xx yy zz"],
+        ];
+        let mut lines = Lines {
+            rows: &rows,
+            wrapping: Wrapping::Greedy,
+            cache: HashMap::new(),
+        };
+        assert_eq!(lines.height(&[5, 4]), 3 + 3 + 3);
+        let widths = minimize_height(&rows, &[11, 8], vec![5, 4], 9, Wrapping::Greedy);
+        assert_eq!(widths, vec![6, 3]);
+        assert_eq!(lines.height(&widths), 2 + 2 + 3);
+    }
+}
@@ -0,0 +1,134 @@
+//! Characters used to draw separators and frames.
+
+/// All characters needed to draw a table.
+///
+/// ```text
+/// top_left   horizontal  top_junction     top_right
+///        ┌───────────────────┬──────────────┐
+/// vertical │  who            │ what         │
+///        ├───────────────────┼──────────────┤
+/// left_junction            cross     right_junction
+///        └───────────────────┴──────────────┘
+/// bottom_left      bottom_junction   bottom_right
+/// ```
+///
+/// Only `vertical`, `horizontal` and `cross` are used unless the table is
+/// framed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BorderChars<'a> {
+    pub horizontal: &'a str,
+    pub vertical: &'a str,
+    pub cross: &'a str,
+    pub top_left: &'a str,
+    pub top_right: &'a str,
+    pub bottom_left: &'a str,
+    pub bottom_right: &'a str,
+    pub top_junction: &'a str,
+    pub bottom_junction: &'a str,
+    pub left_junction: &'a str,
+    pub right_junction: &'a str,
+}
+
+impl<'a> BorderChars<'a> {
+    /// `|`, `-` and `+` everywhere else.
+    pub fn ascii() -> BorderChars<'static> {
+        BorderChars {
+            horizontal: "-",
+            vertical: "|",
+            cross: "+",
+            top_left: "+",
+            top_right: "+",
+            bottom_left: "+",
+            bottom_right: "+",
+            top_junction: "+",
+            bottom_junction: "+",
+            left_junction: "+",
+            right_junction: "+",
+        }
+    }
+
+    /// Light box drawing characters.
+    pub fn unicode() -> BorderChars<'static> {
+        BorderChars {
+            horizontal: "─",
+            vertical: "│",
+            cross: "┼",
+            top_left: "┌",
+            top_right: "┐",
+            bottom_left: "└",
+            bottom_right: "┘",
+            top_junction: "┬",
+            bottom_junction: "┴",
+            left_junction: "├",
+            right_junction: "┤",
+        }
+    }
+
+    /// Like `unicode` with rounded corners.
+    pub fn rounded() -> BorderChars<'static> {
+        BorderChars {
+            top_left: "╭",
+            top_right: "╮",
+            bottom_left: "╰",
+            bottom_right: "╯",
+            ..BorderChars::unicode()
+        }
+    }
+
+    /// No lines at all, columns are separated by two spaces.
+    pub fn borderless() -> BorderChars<'static> {
+        BorderChars {
+            horizontal: "",
+            vertical: "  ",
+            cross: "",
+            top_left: "",
+            top_right: "",
+            bottom_left: "",
+            bottom_right: "",
+            top_junction: "",
+            bottom_junction: "",
+            left_junction: "",
+            right_junction: "",
+        }
+    }
+}
+
+/// `BorderChars::ascii()`.
+impl<'a> Default for BorderChars<'a> {
+    fn default() -> BorderChars<'a> {
+        BorderChars::ascii()
+    }
+}
+
+/// Characters of a single horizontal line.
+///
+/// `left` and `right` are only used if the table is framed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RuleChars<'a> {
+    pub left: &'a str,
+    pub horizontal: &'a str,
+    pub cross: &'a str,
+    pub right: &'a str,
+}
+
+impl<'a> RuleChars<'a> {
+    /// `=` and `+`.
+    pub fn ascii_double() -> RuleChars<'static> {
+        RuleChars {
+            left: "+",
+            horizontal: "=",
+            cross: "+",
+            right: "+",
+        }
+    }
+
+    /// Double box drawing lines joining light vertical ones.
+    pub fn double() -> RuleChars<'static> {
+        RuleChars {
+            left: "╞",
+            horizontal: "═",
+            cross: "╪",
+            right: "╡",
+        }
+    }
+}
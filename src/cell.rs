@@ -0,0 +1,516 @@
+//! Single cells with their own alignment, style or content which adapts to
+//! the width of their column.
+use std::borrow::Cow;
+use std::cmp;
+use std::fmt::{self, Display};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+#[cfg(feature = "csv")]
+use csv;
+
+use format;
+use {Alignment, BoolFormat, Row, Style, Table};
+
+/// A body cell which can override the alignment and style of its column.
+///
+/// Add cells with `Table::add_cells`, usually through a `RowBuilder`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Cell<'a> {
+    pub(crate) content: Cow<'a, str>,
+    pub(crate) alignment: Option<Alignment>,
+    pub(crate) style: Option<Style>,
+    pub(crate) dynamic: Option<Dynamic<'a>>,
+    pub(crate) none: bool,
+    pub(crate) borders: Option<CellBorders<'a>>,
+}
+
+impl<'a> Cell<'a> {
+    pub fn new<S: Into<Cow<'a, str>>>(content: S) -> Cell<'a> {
+        Cell {
+            content: content.into(),
+            alignment: None,
+            style: None,
+            dynamic: None,
+            none: false,
+            borders: None,
+        }
+    }
+
+    /// A missing value, rendered as the placeholder set with
+    /// `Table::set_none_placeholder`.
+    pub fn none() -> Cell<'a> {
+        Cell {
+            none: true,
+            ..Cell::default()
+        }
+    }
+
+    /// `value` rendered and styled by `format`.
+    ///
+    /// ```
+    /// use lazytable::{BoolFormat, Cell};
+    ///
+    /// assert_eq!(Cell::from_bool(true, BoolFormat::YesNo).content(), "yes");
+    /// ```
+    pub fn from_bool(value: bool, format: BoolFormat) -> Cell<'a> {
+        Cell {
+            style: format.style(value),
+            ..Cell::new(format.text(value))
+        }
+    }
+
+    /// `duration` in its two largest units like `Format::Duration`.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use lazytable::Cell;
+    ///
+    /// assert_eq!(Cell::from_duration(Duration::from_secs(7980)).content(), "2h 13m");
+    /// assert_eq!(Cell::from_duration(Duration::from_millis(450)).content(), "450ms");
+    /// ```
+    pub fn from_duration(duration: Duration) -> Cell<'a> {
+        Cell::new(format::duration(duration))
+    }
+
+    /// A sparkline of `values` filling the width of its column.
+    ///
+    /// The column is as wide as there are values unless it's resized, e.g.
+    /// to fit the table width.
+    ///
+    /// ```
+    /// use lazytable::{Cell, Table, TableConfig};
+    ///
+    /// let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+    /// let mut table = Table::new(TableConfig::default().width(14));
+    /// table.add_cells([Cell::new("load"), Cell::sparkline(&values)]);
+    /// assert_eq!(table.to_string(), " load | ▁▃▄▆█ \n");
+    /// ```
+    pub fn sparkline(values: &[f64]) -> Cell<'a> {
+        let mut cell = Cell::new(sparkline(values, values.len()));
+        cell.dynamic = Some(Dynamic::Sparkline(values.to_vec()));
+        cell
+    }
+
+    /// Align this cell regardless of the alignment of its column.
+    pub fn alignment(mut self, alignment: Alignment) -> Cell<'a> {
+        self.alignment = Some(alignment);
+        self
+    }
+
+    /// Draw the column separators on both sides of this cell with
+    /// `borders`, the frame stays.
+    ///
+    /// ```
+    /// use lazytable::{Cell, CellBorders, Table};
+    ///
+    /// let mut table = Table::default();
+    /// table.add_row(["a", "b", "c"]);
+    /// table.add_cells([Cell::new("d"), Cell::new("e").borders(CellBorders::Hidden), Cell::new("f")]);
+    /// assert_eq!(table.to_string(), " a | b | c \n d   e   f \n");
+    /// ```
+    pub fn borders(mut self, borders: CellBorders<'a>) -> Cell<'a> {
+        self.borders = Some(borders);
+        self
+    }
+
+    /// Style this cell, taking precedence over row styles and rules.
+    pub fn style(mut self, style: Style) -> Cell<'a> {
+        self.style = Some(style);
+        self
+    }
+
+    /// A progress bar like `[=====>   ]  62%` filling the width of its
+    /// column.
+    ///
+    /// `fraction` is clamped to `0.0..=1.0`. Without room for the bar only
+    /// the percentage is shown.
+    ///
+    /// ```
+    /// use lazytable::{Cell, Table, TableConfig};
+    ///
+    /// let mut table = Table::default();
+    /// table.add_cells([Cell::new("linux.iso"), Cell::progress(0.62)]);
+    /// assert_eq!(table.to_string(), " linux.iso | [=====>   ]  62% \n");
+    /// let mut table = Table::new(TableConfig::default().width(24));
+    /// table.add_cells([Cell::new("linux.iso"), Cell::progress(0.62)]);
+    /// assert_eq!(table.to_string(), " linux.iso | [=> ]  62% \n");
+    /// ```
+    pub fn progress(fraction: f64) -> Cell<'a> {
+        let dynamic = Dynamic::Progress(fraction);
+        let mut cell = Cell::new(dynamic.render(PROGRESS_WIDTH, false));
+        cell.dynamic = Some(dynamic);
+        cell
+    }
+
+    /// A cell showing `table`, rendered to the width of its column.
+    ///
+    /// ```
+    /// use lazytable::{Cell, Table, TableConfig};
+    ///
+    /// let mut steps = Table::default();
+    /// steps.add_row(["build", "ok"]);
+    /// steps.add_row(["test", "failed"]);
+    /// let mut table = Table::new(TableConfig::default().width(24));
+    /// table.set_title(["job", "steps"]);
+    /// table.add_cells([Cell::new("ci"), Cell::table(steps)]);
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     " job | steps            \n\
+    ///     -----+------------------\n \
+    ///     ci  |  build | ok      \n     \
+    ///         |  test  | failed  \n"
+    /// );
+    /// ```
+    pub fn table(table: Table<'a>) -> Cell<'a> {
+        let dynamic = Dynamic::Table(Nested(Arc::new(Mutex::new(table))));
+        let width = dynamic.natural_width();
+        let mut cell = Cell::new(dynamic.render(width, false));
+        cell.dynamic = Some(dynamic);
+        cell
+    }
+
+    /// The text of this cell.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+impl<'a> From<&'a str> for Cell<'a> {
+    fn from(content: &'a str) -> Cell<'a> {
+        Cell::new(content)
+    }
+}
+
+impl<'a> From<String> for Cell<'a> {
+    fn from(content: String) -> Cell<'a> {
+        Cell::new(content)
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for Cell<'a> {
+    fn from(content: Cow<'a, str>) -> Cell<'a> {
+        Cell::new(content)
+    }
+}
+
+/// Content rendered for the width of its column.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Dynamic<'a> {
+    Sparkline(Vec<f64>),
+    Progress(f64),
+    Table(Nested<'a>),
+}
+
+impl<'a> Dynamic<'a> {
+    /// The content for a column `width` wide, nested tables are styled only
+    /// if `colored`.
+    pub(crate) fn render(&self, width: usize, colored: bool) -> String {
+        match *self {
+            Dynamic::Sparkline(ref values) => sparkline(values, width),
+            Dynamic::Progress(fraction) => progress(fraction, width),
+            Dynamic::Table(ref nested) => {
+                let mut table = nested.lock();
+                table.config.width = width;
+                let color = table.color.replace(colored);
+                let mut lines = table.to_string();
+                table.color = color;
+                lines.truncate(lines.trim_end_matches(['\r', '\n']).len());
+                lines
+            }
+        }
+    }
+
+    /// The width of the nested table as configured.
+    fn natural_width(&self) -> usize {
+        match *self {
+            Dynamic::Table(ref nested) => nested.lock().config.width,
+            _ => 0,
+        }
+    }
+}
+
+/// A table in a cell, shared by clones of the cell.
+#[derive(Clone)]
+pub(crate) struct Nested<'a>(Arc<Mutex<Table<'a>>>);
+
+impl<'a> Nested<'a> {
+    /// The nested table, rendering it again after a panic is fine.
+    fn lock(&self) -> MutexGuard<'_, Table<'a>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<'a> fmt::Debug for Nested<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Nested")
+    }
+}
+
+impl<'a> PartialEq for Nested<'a> {
+    fn eq(&self, other: &Nested<'a>) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// The natural width of progress bars, nine steps and the percentage.
+const PROGRESS_WIDTH: usize = 16;
+
+/// A progress bar `width` characters wide.
+fn progress(fraction: f64, width: usize) -> String {
+    let fraction = if fraction.is_nan() {
+        0.0
+    } else {
+        fraction.clamp(0.0, 1.0)
+    };
+    let percent = format!("{:>4}", format!("{:.0}%", fraction * 100.0));
+    // Brackets and a space around the bar.
+    let inner = match width.checked_sub(percent.len() + 3) {
+        Some(inner) if inner > 0 => inner,
+        _ => return percent.trim_start().to_owned(),
+    };
+    let filled = (fraction * inner as f64).round() as usize;
+    let mut bar = String::with_capacity(width);
+    bar.push('[');
+    for i in 0..inner {
+        bar.push(match i {
+            _ if i + 1 < filled || filled == inner => '=',
+            _ if i + 1 == filled => '>',
+            _ => ' ',
+        });
+    }
+    bar.push_str("] ");
+    bar.push_str(&percent);
+    bar
+}
+
+/// Column separators next to a cell, overriding the border.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellBorders<'a> {
+    /// Blank separators.
+    Hidden,
+    /// Separators drawn with this string, which should be as wide as the
+    /// border's.
+    Vertical(&'a str),
+}
+
+/// Alignment, style and dynamic content of a body cell added with
+/// `Table::add_cells`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct CellSettings<'a> {
+    pub(crate) alignment: Option<Alignment>,
+    pub(crate) style: Option<Style>,
+    pub(crate) dynamic: Option<Dynamic<'a>>,
+    pub(crate) none: bool,
+    pub(crate) borders: Option<CellBorders<'a>>,
+}
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Bars like `▁▂▅▇` of `values`, `width` characters wide.
+///
+/// Values are averaged or repeated to fit the width. Bars scale from the
+/// smallest to the largest value, values which aren't finite are blank.
+///
+/// ```
+/// use lazytable::cell::sparkline;
+///
+/// assert_eq!(sparkline(&[0.0, 1.0, 2.0, 7.0], 4), "▁▂▃█");
+/// assert_eq!(sparkline(&[0.0, 1.0, 2.0, 7.0], 2), "▂▆");
+/// assert_eq!(sparkline(&[0.0, 1.0], 4), "▁▁██");
+/// ```
+pub fn sparkline(values: &[f64], width: usize) -> String {
+    let finite = || values.iter().cloned().filter(|v| v.is_finite());
+    let min = finite().fold(f64::INFINITY, f64::min);
+    let max = finite().fold(f64::NEG_INFINITY, f64::max);
+    let n = values.len();
+    if n == 0 {
+        return String::new();
+    }
+    (0..width)
+        .map(|i| {
+            let start = i * n / width;
+            let bucket = &values[start..cmp::max((i + 1) * n / width, start + 1)];
+            let value = bucket.iter().sum::<f64>() / bucket.len() as f64;
+            if !value.is_finite() {
+                ' '
+            } else if max > min {
+                let level = (value - min) / (max - min) * (BARS.len() - 1) as f64;
+                BARS[level.round() as usize]
+            } else {
+                BARS[BARS.len() / 2 - 1]
+            }
+        })
+        .collect()
+}
+
+/// Builds a row from values of different types.
+///
+/// ```
+/// use lazytable::{Alignment, RowBuilder, Style, Table};
+///
+/// let mut table = Table::default();
+/// table.add_cells(
+///     RowBuilder::new()
+///         .push("disk")
+///         .push_aligned(93.5, Alignment::Right)
+///         .push_styled(true, Style::red()),
+/// );
+/// table.add_row(RowBuilder::new().push("memory").push(1024.25).build());
+/// assert_eq!(
+///     table.to_string(),
+///     " disk   |    93.5 | true \n memory | 1024.25 \n"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RowBuilder<'a> {
+    cells: Vec<Cell<'a>>,
+}
+
+impl<'a> RowBuilder<'a> {
+    pub fn new() -> RowBuilder<'a> {
+        RowBuilder::default()
+    }
+
+    /// Append `value` as it is displayed.
+    pub fn push<T: Display>(self, value: T) -> RowBuilder<'a> {
+        self.push_cell(Cell::new(value.to_string()))
+    }
+
+    /// Append `value` aligned regardless of the alignment of its column.
+    pub fn push_aligned<T: Display>(self, value: T, alignment: Alignment) -> RowBuilder<'a> {
+        self.push_cell(Cell::new(value.to_string()).alignment(alignment))
+    }
+
+    /// Append `value` styled, see `Cell::style`.
+    pub fn push_styled<T: Display>(self, value: T, style: Style) -> RowBuilder<'a> {
+        self.push_cell(Cell::new(value.to_string()).style(style))
+    }
+
+    /// Append `value`, `None` as `Cell::none`.
+    ///
+    /// ```
+    /// use lazytable::{RowBuilder, Table};
+    ///
+    /// let mut table = Table::default();
+    /// table.set_none_placeholder("n/a");
+    /// table.add_cells(RowBuilder::new().push_option(Some(1)).push_option(None::<u8>));
+    /// assert_eq!(table.to_string(), " 1 | n/a \n");
+    /// ```
+    pub fn push_option<T: Display>(self, value: Option<T>) -> RowBuilder<'a> {
+        match value {
+            Some(value) => self.push(value),
+            None => self.push_cell(Cell::none()),
+        }
+    }
+
+    /// Append a cell.
+    pub fn push_cell<C: Into<Cell<'a>>>(mut self, cell: C) -> RowBuilder<'a> {
+        self.cells.push(cell.into());
+        self
+    }
+
+    /// The cells as a plain row.
+    ///
+    /// Alignments and styles of the cells are dropped, add the builder with
+    /// `Table::add_cells` to keep them.
+    pub fn build(self) -> Row<'a> {
+        self.cells.into_iter().map(|c| c.content).collect()
+    }
+}
+
+/// Values accepted by the `row!` macro.
+///
+/// `None` becomes an empty cell, a `Row` can't tell it apart from an empty
+/// value. Use `RowBuilder::push_option` to render it as the placeholder set
+/// with `Table::set_none_placeholder`.
+pub trait RowValue<'a> {
+    fn into_cow(self) -> Cow<'a, str>;
+}
+
+impl<'a> RowValue<'a> for &'a str {
+    fn into_cow(self) -> Cow<'a, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl<'a> RowValue<'a> for &'a String {
+    fn into_cow(self) -> Cow<'a, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl<'a> RowValue<'a> for String {
+    fn into_cow(self) -> Cow<'a, str> {
+        Cow::Owned(self)
+    }
+}
+
+impl<'a> RowValue<'a> for Cow<'a, str> {
+    fn into_cow(self) -> Cow<'a, str> {
+        self
+    }
+}
+
+impl<'a, T: Display> RowValue<'a> for Option<T> {
+    fn into_cow(self) -> Cow<'a, str> {
+        self.map_or(Cow::Borrowed(""), |value| Cow::Owned(value.to_string()))
+    }
+}
+
+impl<'a> IntoIterator for RowBuilder<'a> {
+    type Item = Cell<'a>;
+    type IntoIter = ::std::vec::IntoIter<Cell<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.into_iter()
+    }
+}
+
+#[cfg(feature = "csv")]
+impl<'a> From<&'a csv::StringRecord> for RowBuilder<'a> {
+    fn from(record: &'a csv::StringRecord) -> RowBuilder<'a> {
+        RowBuilder {
+            cells: record.iter().map(Cell::new).collect(),
+        }
+    }
+}
+
+#[cfg(feature = "csv")]
+impl<'a> From<csv::StringRecord> for RowBuilder<'a> {
+    fn from(record: csv::StringRecord) -> RowBuilder<'a> {
+        RowBuilder {
+            cells: record.iter().map(|c| Cell::new(c.to_owned())).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        assert_eq!(progress(0.0, 16), "[         ]   0%");
+        assert_eq!(progress(1.0, 16), "[=========] 100%");
+        assert_eq!(progress(0.5, 9), "[> ]  50%");
+        assert_eq!(progress(f64::NAN, 8), "[ ]   0%");
+        assert_eq!(progress(2.0, 4), "100%");
+        assert_eq!(progress(0.05, 2), "5%");
+    }
+
+    #[test]
+    fn test_row_builder() {
+        let row = RowBuilder::new()
+            .push(1)
+            .push_aligned('x', Alignment::Center)
+            .push_cell("y")
+            .build();
+        assert_eq!(row, ["1", "x", "y"]);
+        let cells: Vec<_> = RowBuilder::new()
+            .push_styled(2.5, Style::red())
+            .into_iter()
+            .collect();
+        assert_eq!(cells, [Cell::new("2.5").style(Style::red())]);
+    }
+}
@@ -0,0 +1,143 @@
+//! Errors of fallible table operations.
+use std::error;
+use std::fmt;
+use std::io;
+
+#[cfg(feature = "csv")]
+use csv;
+#[cfg(feature = "serde")]
+use ser;
+
+/// Any error of this crate.
+///
+/// ```
+/// use lazytable::{Error, Table, TableConfig};
+///
+/// fn count_rows(text: &str) -> Result<usize, Error> {
+///     let table = Table::from_markdown(text)?;
+///     let columns = table.title().map_or(0, |t| t.len());
+///     TableConfig::default().width(10).validate(columns)?;
+///     Ok(table.rows().len())
+/// }
+///
+/// assert_eq!(count_rows("| a | b |\n|---|---|\n| 1 | 2 |").unwrap(), 1);
+/// assert!(count_rows("| a | b | c | d | e |\n|-|-|-|-|-|").is_err());
+/// assert!(count_rows("no table here").is_err());
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Reading or writing failed.
+    Io(io::Error),
+    /// The configuration doesn't suit the table.
+    Config(ConfigError),
+    /// The input couldn't be parsed into a table.
+    Parse(String),
+    /// Column `idx` has `len` cells while the first one has `expected`.
+    ColumnLength {
+        idx: usize,
+        len: usize,
+        expected: usize,
+    },
+    /// A value couldn't be serialized into a table.
+    #[cfg(feature = "serde")]
+    Serialize(ser::Error),
+    /// Reading or writing CSV failed.
+    #[cfg(feature = "csv")]
+    Csv(csv::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => write!(f, "io error: {}", err),
+            Error::Config(ref err) => write!(f, "invalid configuration: {}", err),
+            Error::Parse(ref msg) => write!(f, "parse error: {}", msg),
+            Error::ColumnLength { idx, len, expected } => write!(
+                f,
+                "column {} has {} cells instead of {}",
+                idx, len, expected
+            ),
+            #[cfg(feature = "serde")]
+            Error::Serialize(ref err) => write!(f, "serialize error: {}", err),
+            #[cfg(feature = "csv")]
+            Error::Csv(ref err) => write!(f, "csv error: {}", err),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::Config(ref err) => Some(err),
+            Error::Parse(_) | Error::ColumnLength { .. } => None,
+            #[cfg(feature = "serde")]
+            Error::Serialize(ref err) => Some(err),
+            #[cfg(feature = "csv")]
+            Error::Csv(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+impl From<ConfigError> for Error {
+    fn from(err: ConfigError) -> Error {
+        Error::Config(err)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<ser::Error> for Error {
+    fn from(err: ser::Error) -> Error {
+        Error::Serialize(err)
+    }
+}
+
+#[cfg(feature = "csv")]
+impl From<csv::Error> for Error {
+    fn from(err: csv::Error) -> Error {
+        Error::Csv(err)
+    }
+}
+
+/// A configuration which doesn't suit a table, see `TableConfig::validate`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Not even one character per column fits the width.
+    TooNarrow {
+        width: usize,
+        columns: usize,
+        padding: usize,
+    },
+    /// The key column doesn't exist.
+    KeyColumn { idx: usize, columns: usize },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConfigError::TooNarrow {
+                width,
+                columns,
+                padding,
+            } => write!(
+                f,
+                "width {} cannot fit {} columns with padding {}",
+                width, columns, padding
+            ),
+            ConfigError::KeyColumn { idx, columns } => write!(
+                f,
+                "key column {} is out of range for {} columns",
+                idx, columns
+            ),
+        }
+    }
+}
+
+impl error::Error for ConfigError {}
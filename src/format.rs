@@ -0,0 +1,504 @@
+//! Column formatters applied to body cells at render time.
+use std::borrow::Cow;
+use std::time::Duration;
+
+use {Alignment, Style};
+
+/// Built-in column formatters.
+///
+/// A formatter rewrites the content of every body cell in a column before the
+/// table is measured. Cells that can't be interpreted by the formatter are
+/// rendered unchanged.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Format<'a> {
+    /// Monetary values for an ISO 4217 code like `"USD"` or `"EUR"`.
+    ///
+    /// Renders two decimals with grouped thousands, places the currency
+    /// symbol and wraps negative amounts in parentheses: `($1,234.50)`.
+    /// Unknown codes are appended: `12.00 CHF`.
+    Currency(&'a str),
+    /// Timestamps rendered with a `strftime` pattern like `"%Y-%m-%d %H:%M"`.
+    ///
+    /// Cells are parsed as RFC 3339, `YYYY-MM-DD HH:MM:SS`, `YYYY-MM-DD` or
+    /// Unix seconds. Requires the `chrono` feature.
+    #[cfg(feature = "chrono")]
+    DateTime(&'a str),
+    /// Timestamps rendered relative to `now` like `3 minutes ago` or
+    /// `in 2 days`.
+    ///
+    /// Cells are parsed like `DateTime`, timestamps without offset are UTC.
+    /// `now` is in Unix seconds, the time of rendering if `None`. Requires
+    /// the `chrono` feature.
+    #[cfg(feature = "chrono")]
+    Relative { now: Option<i64> },
+    /// Booleans like `true`, `0` or `yes` rendered consistently.
+    Bool(BoolFormat),
+    /// Numbers rendered with `precision` decimals: `2.5` becomes `2.50`.
+    Float { precision: usize },
+    /// Ratios rendered as percentages with `precision` decimals: `0.073`
+    /// becomes `7.3 %`, or `7.3%` unless `spaced`.
+    Percent { precision: usize, spaced: bool },
+    /// Byte counts rendered with a unit like `1.4 GiB` or `532 KiB`.
+    Bytes(ByteFormat),
+    /// Seconds rendered as compact durations like `2h 13m`, `4.5s` or
+    /// `450ms`, see `Cell::from_duration` for `std::time::Duration`s.
+    Duration,
+}
+
+/// How booleans are rendered, see `Format::Bool` and `Cell::from_bool`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BoolFormat {
+    /// `✓` and `✗`.
+    #[default]
+    Check,
+    /// `✓` in green and `✗` in red.
+    Colored,
+    /// `yes` and `no`.
+    YesNo,
+    /// `Y` and `N`.
+    Yn,
+}
+
+/// The units of `Format::Bytes`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ByteFormat {
+    /// Powers of 1024: `KiB`, `MiB`, `GiB`, …
+    #[default]
+    Binary,
+    /// Powers of 1000: `kB`, `MB`, `GB`, …
+    Si,
+}
+
+impl ByteFormat {
+    /// The text of `bytes`, one decimal below ten units, none above.
+    pub fn text(self, bytes: f64) -> String {
+        let (base, units) = match self {
+            ByteFormat::Binary => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"]),
+            ByteFormat::Si => (1000.0, ["B", "kB", "MB", "GB", "TB", "PB", "EB"]),
+        };
+        let mut value = bytes.abs();
+        let mut unit = 0;
+        while unit + 1 < units.len() && round(value, unit) >= base {
+            value /= base;
+            unit += 1;
+        }
+        let precision = if unit > 0 && round(value, unit) < 10.0 {
+            1
+        } else {
+            0
+        };
+        let sign = if bytes < 0.0 && round(value, unit) > 0.0 {
+            "-"
+        } else {
+            ""
+        };
+        format!("{}{:.*} {}", sign, precision, value, units[unit])
+    }
+}
+
+/// `value` rounded like `ByteFormat::text` shows it in unit `unit`.
+fn round(value: f64, unit: usize) -> f64 {
+    if unit > 0 && value < 9.95 {
+        (value * 10.0).round() / 10.0
+    } else {
+        value.round()
+    }
+}
+
+impl BoolFormat {
+    /// The text of `value`.
+    pub fn text(self, value: bool) -> &'static str {
+        match (self, value) {
+            (BoolFormat::Check, true) | (BoolFormat::Colored, true) => "✓",
+            (BoolFormat::Check, false) | (BoolFormat::Colored, false) => "✗",
+            (BoolFormat::YesNo, true) => "yes",
+            (BoolFormat::YesNo, false) => "no",
+            (BoolFormat::Yn, true) => "Y",
+            (BoolFormat::Yn, false) => "N",
+        }
+    }
+
+    /// The style of `value`, if any.
+    pub fn style(self, value: bool) -> Option<Style> {
+        match (self, value) {
+            (BoolFormat::Colored, true) => Some(Style::green()),
+            (BoolFormat::Colored, false) => Some(Style::red()),
+            _ => None,
+        }
+    }
+}
+
+/// `cell` as a boolean, accepting `true`, `yes`, `y`, `1` and their
+/// opposites in any case.
+pub(crate) fn parse_bool(cell: &str) -> Option<bool> {
+    match cell.trim().to_ascii_lowercase().as_str() {
+        "true" | "yes" | "y" | "1" => Some(true),
+        "false" | "no" | "n" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+impl<'a> Format<'a> {
+    /// The alignment cells of this format default to.
+    pub fn alignment(&self) -> Alignment {
+        match *self {
+            Format::Currency(_) => Alignment::Right,
+            #[cfg(feature = "chrono")]
+            Format::DateTime(_) => Alignment::Left,
+            #[cfg(feature = "chrono")]
+            Format::Relative { .. } => Alignment::Left,
+            Format::Bool(_) => Alignment::Center,
+            Format::Float { .. } | Format::Percent { .. } | Format::Bytes(_) | Format::Duration => {
+                Alignment::Right
+            }
+        }
+    }
+
+    /// Whether formatting the same cell again may give a different text,
+    /// like relative times to the current time.
+    pub(crate) fn is_volatile(&self) -> bool {
+        match *self {
+            #[cfg(feature = "chrono")]
+            Format::Relative { now } => now.is_none(),
+            _ => false,
+        }
+    }
+
+    /// Format a single cell.
+    pub fn apply<'c>(&self, cell: &'c str) -> Cow<'c, str> {
+        match *self {
+            Format::Currency(code) => match cell.trim().parse::<f64>() {
+                Ok(value) if value.is_finite() => Cow::Owned(currency(value, code)),
+                _ => Cow::Borrowed(cell),
+            },
+            #[cfg(feature = "chrono")]
+            Format::DateTime(pattern) => match datetime::format(cell, pattern) {
+                Some(formatted) => Cow::Owned(formatted),
+                None => Cow::Borrowed(cell),
+            },
+            #[cfg(feature = "chrono")]
+            Format::Relative { now } => match datetime::relative(cell, now) {
+                Some(relative) => Cow::Owned(relative),
+                None => Cow::Borrowed(cell),
+            },
+            Format::Bool(format) => match parse_bool(cell) {
+                Some(value) => Cow::Borrowed(format.text(value)),
+                None => Cow::Borrowed(cell),
+            },
+            Format::Float { precision } => match cell.trim().parse::<f64>() {
+                Ok(value) => Cow::Owned(float(value, precision)),
+                Err(_) => Cow::Borrowed(cell),
+            },
+            Format::Percent { precision, spaced } => match cell.trim().parse::<f64>() {
+                Ok(value) => {
+                    let sign = if spaced { " %" } else { "%" };
+                    Cow::Owned(float(value * 100.0, precision) + sign)
+                }
+                Err(_) => Cow::Borrowed(cell),
+            },
+            Format::Bytes(format) => match cell.trim().parse::<f64>() {
+                Ok(value) if value.is_finite() => Cow::Owned(format.text(value)),
+                _ => Cow::Borrowed(cell),
+            },
+            Format::Duration => match cell.trim().parse::<f64>() {
+                Ok(secs) => match Duration::try_from_secs_f64(secs.abs()) {
+                    Ok(d) => {
+                        let text = duration(d);
+                        if secs < 0.0 && text != "0s" {
+                            Cow::Owned(format!("-{}", text))
+                        } else {
+                            Cow::Owned(text)
+                        }
+                    }
+                    Err(_) => Cow::Borrowed(cell),
+                },
+                Err(_) => Cow::Borrowed(cell),
+            },
+        }
+    }
+
+    /// The style of a formatted cell, if the format has one for it.
+    pub(crate) fn style(&self, cell: &str) -> Option<Style> {
+        match *self {
+            Format::Bool(format) => format.style(parse_bool(cell)?),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod datetime {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+    use std::fmt::Write;
+
+    pub fn format(cell: &str, pattern: &str) -> Option<String> {
+        let datetime = parse(cell.trim())?;
+        let mut formatted = String::new();
+        // chrono reports invalid patterns as fmt errors.
+        write!(formatted, "{}", datetime.format(pattern)).ok()?;
+        Some(formatted)
+    }
+
+    pub fn relative(cell: &str, now: Option<i64>) -> Option<String> {
+        let cell = cell.trim();
+        let timestamp = match DateTime::parse_from_rfc3339(cell) {
+            Ok(datetime) => datetime.timestamp(),
+            Err(_) => parse(cell)?.and_utc().timestamp(),
+        };
+        let now = now.unwrap_or_else(|| Utc::now().timestamp());
+        let diff = timestamp.checked_sub(now)?;
+        if diff == 0 {
+            return Some("now".to_owned());
+        }
+        let secs = diff.unsigned_abs();
+        let (count, unit) = [
+            (31_536_000, "year"),
+            (2_592_000, "month"),
+            (86_400, "day"),
+            (3_600, "hour"),
+            (60, "minute"),
+        ]
+        .iter()
+        .find(|&&(unit, _)| secs >= unit)
+        .map_or((secs, "second"), |&(unit, name)| (secs / unit, name));
+        let plural = if count == 1 { "" } else { "s" };
+        Some(if diff < 0 {
+            format!("{} {}{} ago", count, unit, plural)
+        } else {
+            format!("in {} {}{}", count, unit, plural)
+        })
+    }
+
+    fn parse(cell: &str) -> Option<NaiveDateTime> {
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(cell) {
+            return Some(datetime.naive_local());
+        }
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(cell, "%Y-%m-%d %H:%M:%S") {
+            return Some(datetime);
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(cell, "%Y-%m-%d") {
+            return date.and_hms_opt(0, 0, 0);
+        }
+        cell.parse::<i64>()
+            .ok()
+            .and_then(|secs| DateTime::from_timestamp(secs, 0))
+            .map(|datetime| datetime.naive_utc())
+    }
+}
+
+/// `d` in its two largest units from a minute on, in seconds, milli-, micro-
+/// or nanoseconds below.
+pub(crate) fn duration(d: Duration) -> String {
+    const NANOS: [(u128, &str); 4] = [
+        (86_400_000_000_000, "d"),
+        (3_600_000_000_000, "h"),
+        (60_000_000_000, "m"),
+        (1_000_000_000, "s"),
+    ];
+    let nanos = d.as_nanos();
+    for pair in NANOS.windows(2) {
+        let ((big, big_name), (small, small_name)) = (pair[0], pair[1]);
+        let rounded = (nanos + small / 2) / small * small;
+        if rounded >= big {
+            return match (rounded % big) / small {
+                0 => format!("{}{}", rounded / big, big_name),
+                rest => format!("{}{} {}{}", rounded / big, big_name, rest, small_name),
+            };
+        }
+    }
+    let secs = d.as_secs_f64();
+    if secs >= 9.95 {
+        format!("{:.0}s", secs)
+    } else if secs >= 0.95 {
+        format!("{:.1}s", secs)
+    } else if secs >= 0.5e-3 {
+        format!("{:.0}ms", secs * 1e3)
+    } else if secs >= 0.5e-6 {
+        format!("{:.0}µs", secs * 1e6)
+    } else if nanos > 0 {
+        format!("{}ns", nanos)
+    } else {
+        "0s".to_owned()
+    }
+}
+
+fn currency(value: f64, code: &str) -> String {
+    let amount = group_thousands(&format!("{:.2}", value.abs()));
+    let amount = match currency_symbol(code) {
+        Some(symbol) => format!("{}{}", symbol, amount),
+        None => format!("{} {}", amount, code),
+    };
+    if value < 0.0 && value.abs() >= 0.005 {
+        format!("({})", amount)
+    } else {
+        amount
+    }
+}
+
+/// `value` with `precision` decimals, without a sign if it rounds to zero.
+fn float(value: f64, precision: usize) -> String {
+    let formatted = format!("{:.*}", precision, value);
+    match formatted.strip_prefix('-') {
+        Some(abs) if abs.bytes().all(|b| b == b'0' || b == b'.') => abs.to_owned(),
+        _ => formatted,
+    }
+}
+
+fn currency_symbol(code: &str) -> Option<&'static str> {
+    match code {
+        "USD" | "AUD" | "CAD" | "NZD" => Some("$"),
+        "EUR" => Some("€"),
+        "GBP" => Some("£"),
+        "JPY" | "CNY" => Some("¥"),
+        "INR" => Some("₹"),
+        _ => None,
+    }
+}
+
+/// Insert `,` between groups of three digits in front of the decimal point.
+fn group_thousands(number: &str) -> String {
+    let (int, frac) = match number.find('.') {
+        Some(i) => number.split_at(i),
+        None => (number, ""),
+    };
+    let mut grouped = String::with_capacity(number.len() + int.len() / 3);
+    for (i, c) in int.chars().enumerate() {
+        if i > 0 && (int.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    grouped.push_str(frac);
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bool() {
+        let format = Format::Bool(BoolFormat::YesNo);
+        assert_eq!(format.apply(" TRUE"), "yes");
+        assert_eq!(format.apply("0"), "no");
+        assert_eq!(format.apply("maybe"), "maybe");
+        let format = Format::Bool(BoolFormat::Colored);
+        assert_eq!(format.apply("y"), "✓");
+        assert_eq!(format.style("n"), Some(Style::red()));
+        assert_eq!(format.style("?"), None);
+        assert_eq!(Format::Currency("USD").style("1"), None);
+    }
+
+    #[test]
+    fn test_currency() {
+        let usd = Format::Currency("USD");
+        assert_eq!(usd.apply("1234.5"), "$1,234.50");
+        assert_eq!(usd.apply("-3"), "($3.00)");
+        assert_eq!(usd.apply("999"), "$999.00");
+        assert_eq!(usd.apply("n/a"), "n/a");
+        assert_eq!(usd.apply("inf"), "inf");
+        assert_eq!(usd.apply("NaN"), "NaN");
+        assert_eq!(Format::Currency("EUR").apply("1000000"), "€1,000,000.00");
+        assert_eq!(Format::Currency("CHF").apply("12"), "12.00 CHF");
+    }
+
+    #[test]
+    fn test_float() {
+        let format = Format::Float { precision: 2 };
+        assert_eq!(format.alignment(), Alignment::Right);
+        assert_eq!(format.apply("2.5"), "2.50");
+        assert_eq!(format.apply(" 3.14159"), "3.14");
+        assert_eq!(format.apply("-1e3"), "-1000.00");
+        assert_eq!(format.apply("-0.001"), "0.00");
+        assert_eq!(format.apply("n/a"), "n/a");
+        assert_eq!(Format::Float { precision: 0 }.apply("2.7"), "3");
+    }
+
+    #[test]
+    fn test_percent() {
+        let format = Format::Percent {
+            precision: 1,
+            spaced: true,
+        };
+        assert_eq!(format.alignment(), Alignment::Right);
+        assert_eq!(format.apply("0.073"), "7.3 %");
+        assert_eq!(format.apply("1"), "100.0 %");
+        assert_eq!(format.apply("-0.0001"), "0.0 %");
+        assert_eq!(format.apply("many"), "many");
+        let format = Format::Percent {
+            precision: 0,
+            spaced: false,
+        };
+        assert_eq!(format.apply("0.5"), "50%");
+    }
+
+    #[test]
+    fn test_bytes() {
+        let binary = Format::Bytes(ByteFormat::Binary);
+        assert_eq!(binary.alignment(), Alignment::Right);
+        assert_eq!(binary.apply("0"), "0 B");
+        assert_eq!(binary.apply("1023"), "1023 B");
+        assert_eq!(binary.apply("544768"), "532 KiB");
+        assert_eq!(binary.apply("1503238553"), "1.4 GiB");
+        assert_eq!(binary.apply("1048575"), "1.0 MiB");
+        assert_eq!(binary.apply("-2048"), "-2.0 KiB");
+        assert_eq!(binary.apply("big"), "big");
+        let si = Format::Bytes(ByteFormat::Si);
+        assert_eq!(si.apply("1500"), "1.5 kB");
+        assert_eq!(si.apply("999999"), "1.0 MB");
+        assert_eq!(si.apply("25000000"), "25 MB");
+    }
+
+    #[test]
+    fn test_duration() {
+        let format = Format::Duration;
+        assert_eq!(format.alignment(), Alignment::Right);
+        assert_eq!(format.apply("7980"), "2h 13m");
+        assert_eq!(format.apply("7200"), "2h");
+        assert_eq!(format.apply("93784"), "1d 2h");
+        assert_eq!(format.apply("59.7"), "1m");
+        assert_eq!(format.apply("61"), "1m 1s");
+        assert_eq!(format.apply("12.4"), "12s");
+        assert_eq!(format.apply("4.54"), "4.5s");
+        assert_eq!(format.apply("0.45"), "450ms");
+        assert_eq!(format.apply("0.000012"), "12µs");
+        assert_eq!(format.apply("0"), "0s");
+        assert_eq!(format.apply("-90"), "-1m 30s");
+        assert_eq!(format.apply("later"), "later");
+        assert_eq!(format.apply("1e20"), "1e20");
+        assert_eq!(format.apply("inf"), "inf");
+        assert_eq!(duration(Duration::from_nanos(80)), "80ns");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_datetime() {
+        let date = Format::DateTime("%d.%m.%Y %H:%M");
+        assert_eq!(date.apply("2018-03-01T12:30:00+01:00"), "01.03.2018 12:30");
+        assert_eq!(date.apply("2018-03-01 08:05:00"), "01.03.2018 08:05");
+        assert_eq!(date.apply("2018-03-01"), "01.03.2018 00:00");
+        assert_eq!(date.apply("0"), "01.01.1970 00:00");
+        assert_eq!(date.apply("soon"), "soon");
+        assert_eq!(Format::DateTime("%Q").apply("0"), "0");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_relative() {
+        let now = 1_520_000_000;
+        let relative = Format::Relative { now: Some(now) };
+        assert_eq!(relative.apply(&(now - 180).to_string()), "3 minutes ago");
+        assert_eq!(
+            relative.apply(&(now + 2 * 86_400 + 5).to_string()),
+            "in 2 days"
+        );
+        assert_eq!(relative.apply(&(now - 1).to_string()), "1 second ago");
+        assert_eq!(relative.apply(&now.to_string()), "now");
+        assert_eq!(relative.apply("2018-03-02T14:13:20+01:00"), "1 hour ago");
+        assert_eq!(relative.apply("2017-01-01"), "1 year ago");
+        assert_eq!(relative.apply("soon"), "soon");
+        let past = Format::Relative { now: None }.apply("1970-01-02");
+        assert!(past.ends_with(" years ago"), "{}", past);
+    }
+}
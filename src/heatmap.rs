@@ -0,0 +1,118 @@
+//! Coloring numeric columns by value.
+use {Color, Style};
+
+/// Colors the numeric cells of a column from green over yellow to red.
+///
+/// ```
+/// use lazytable::{Heatmap, Table};
+///
+/// let mut table = Table::default();
+/// table.set_column_heatmap(1, Heatmap::new().range(0.0, 100.0).background(true));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Heatmap {
+    range: Option<(f64, f64)>,
+    background: bool,
+}
+
+const GREEN: (f64, f64, f64) = (87.0, 187.0, 138.0);
+const YELLOW: (f64, f64, f64) = (255.0, 214.0, 102.0);
+const RED: (f64, f64, f64) = (230.0, 124.0, 115.0);
+
+impl Heatmap {
+    /// A heatmap from the smallest to the largest value of the column.
+    pub fn new() -> Heatmap {
+        Heatmap::default()
+    }
+
+    /// Map `min` to green and `max` to red, values outside are clamped.
+    pub fn range(mut self, min: f64, max: f64) -> Heatmap {
+        self.range = Some((min, max));
+        self
+    }
+
+    /// Color the background with black text instead of the text.
+    pub fn background(mut self, background: bool) -> Heatmap {
+        self.background = background;
+        self
+    }
+
+    /// The range of the column with `cells`.
+    pub(crate) fn range_of<'c, I>(&self, cells: I) -> Option<(f64, f64)>
+    where
+        I: IntoIterator<Item = &'c str>,
+    {
+        self.range.or_else(|| {
+            cells
+                .into_iter()
+                .filter_map(number)
+                .fold(None, |range, n| match range {
+                    Some((min, max)) => Some((f64::min(min, n), f64::max(max, n))),
+                    None => Some((n, n)),
+                })
+        })
+    }
+
+    /// The style of `cell` in a column spanning `range`.
+    pub(crate) fn style(&self, cell: &str, range: (f64, f64)) -> Option<Style> {
+        let (min, max) = range;
+        let n = number(cell)?;
+        let t = if max > min {
+            ((n - min) / (max - min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let color = if t < 0.5 {
+            mix(GREEN, YELLOW, t * 2.0)
+        } else {
+            mix(YELLOW, RED, t * 2.0 - 1.0)
+        };
+        Some(if self.background {
+            Style::black().on(color)
+        } else {
+            Style::fg(color)
+        })
+    }
+}
+
+fn number(cell: &str) -> Option<f64> {
+    cell.trim().parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+/// The color `t` of the way from `a` to `b`.
+fn mix(a: (f64, f64, f64), b: (f64, f64, f64), t: f64) -> Color {
+    let channel = |a: f64, b: f64| (a + (b - a) * t).round() as u8;
+    Color::Rgb(channel(a.0, b.0), channel(a.1, b.1), channel(a.2, b.2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heatmap() {
+        let heatmap = Heatmap::new();
+        let range = heatmap.range_of(vec!["5", "x", " 1", "3"]).unwrap();
+        assert_eq!(range, (1.0, 5.0));
+        assert_eq!(
+            heatmap.style("1", range),
+            Some(Style::fg(Color::Rgb(87, 187, 138)))
+        );
+        assert_eq!(
+            heatmap.style("3", range),
+            Some(Style::fg(Color::Rgb(255, 214, 102)))
+        );
+        assert_eq!(
+            heatmap.style("9", range),
+            Some(Style::fg(Color::Rgb(230, 124, 115)))
+        );
+        assert_eq!(heatmap.style("x", range), None);
+        let fixed = Heatmap::new().range(0.0, 10.0).background(true);
+        assert_eq!(fixed.range_of(vec!["50"]), Some((0.0, 10.0)));
+        assert_eq!(
+            fixed.style("0", (0.0, 10.0)),
+            Some(Style::black().on(Color::Rgb(87, 187, 138)))
+        );
+        assert_eq!(heatmap.range_of(vec!["x"]), None);
+    }
+}
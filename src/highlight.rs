@@ -0,0 +1,79 @@
+//! Highlighting of search terms at render time.
+use std::ops::Range;
+
+#[cfg(feature = "regex")]
+use regex::Regex;
+use Style;
+
+/// What to highlight.
+pub(crate) enum Pattern<'a> {
+    Text(&'a str),
+    #[cfg(feature = "regex")]
+    Regex(Regex),
+}
+
+impl<'a> Pattern<'a> {
+    /// Byte ranges of all non-empty matches in `cell`.
+    fn find(&self, cell: &str) -> Vec<Range<usize>> {
+        match *self {
+            Pattern::Text("") => vec![],
+            Pattern::Text(text) => cell
+                .match_indices(text)
+                .map(|(i, m)| i..i + m.len())
+                .collect(),
+            #[cfg(feature = "regex")]
+            Pattern::Regex(ref regex) => regex
+                .find_iter(cell)
+                .filter(|m| !m.as_str().is_empty())
+                .map(|m| m.range())
+                .collect(),
+        }
+    }
+}
+
+/// Non-overlapping, sorted ranges of `cell` to highlight.
+///
+/// If matches of several patterns overlap the one starting first wins.
+pub(crate) fn marks(cell: &str, highlights: &[(Pattern, Style)]) -> Vec<(Range<usize>, Style)> {
+    let mut found: Vec<_> = highlights
+        .iter()
+        .flat_map(|(pattern, style)| pattern.find(cell).into_iter().map(move |r| (r, *style)))
+        .collect();
+    found.sort_by_key(|(r, _)| r.start);
+    let mut marks: Vec<(Range<usize>, Style)> = vec![];
+    for (range, style) in found {
+        if !matches!(marks.last(), Some((last, _)) if last.end > range.start) {
+            marks.push((range, style));
+        }
+    }
+    marks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marks() {
+        let highlights = vec![
+            (Pattern::Text("ab"), Style::red()),
+            (Pattern::Text("bc"), Style::blue()),
+            (Pattern::Text(""), Style::green()),
+        ];
+        assert_eq!(
+            marks("abcbc", &highlights),
+            vec![(0..2, Style::red()), (3..5, Style::blue())]
+        );
+        assert_eq!(marks("xyz", &highlights), vec![]);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_regex() {
+        let highlights = vec![(Pattern::Regex(Regex::new("[0-9]+").unwrap()), Style::red())];
+        assert_eq!(
+            marks("a12b3", &highlights),
+            vec![(1..3, Style::red()), (4..5, Style::red())]
+        );
+    }
+}
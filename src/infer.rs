@@ -0,0 +1,86 @@
+//! Guessing the type of columns from their cells.
+use format::parse_bool;
+
+/// The kind of values in a column, see `Table::infer_column_types`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnType {
+    /// Whole numbers like `-12`.
+    Integer,
+    /// Numbers with up to `precision` decimals like `3.25`.
+    Float { precision: usize },
+    /// Dates like `2018-03-01`, optionally followed by a time.
+    Date,
+    /// Booleans like `true` or `no`.
+    Bool,
+    /// Anything else, including columns without values.
+    Text,
+}
+
+/// The narrowest type fitting all non-empty `cells`.
+pub(crate) fn column_type<'c, I: IntoIterator<Item = &'c str>>(cells: I) -> ColumnType {
+    let cells: Vec<&str> = cells
+        .into_iter()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .collect();
+    if cells.is_empty() {
+        ColumnType::Text
+    } else if cells.iter().all(|c| c.parse::<i64>().is_ok()) {
+        ColumnType::Integer
+    } else if cells.iter().all(|c| is_float(c)) {
+        let precision = cells.iter().map(|c| decimals(c)).max().unwrap_or(0);
+        ColumnType::Float { precision }
+    } else if cells.iter().all(|c| is_date(c)) {
+        ColumnType::Date
+    } else if cells.iter().all(|c| parse_bool(c).is_some()) {
+        ColumnType::Bool
+    } else {
+        ColumnType::Text
+    }
+}
+
+/// Whether `cell` is a finite number.
+fn is_float(cell: &str) -> bool {
+    cell.parse::<f64>().is_ok_and(f64::is_finite)
+}
+
+/// The number of digits after the decimal point of `cell`.
+fn decimals(cell: &str) -> usize {
+    let mantissa = cell.split(['e', 'E']).next().unwrap_or(cell);
+    mantissa.find('.').map_or(0, |i| mantissa.len() - i - 1)
+}
+
+/// Whether `cell` starts with a `YYYY-MM-DD` date, followed by nothing or a
+/// time after `T` or a space.
+fn is_date(cell: &str) -> bool {
+    let bytes = cell.as_bytes();
+    bytes.len() >= 10
+        && bytes[..10].iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+        && matches!(bytes.get(10), None | Some(b'T') | Some(b' '))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_type() {
+        assert_eq!(column_type(vec!["1", " -20", ""]), ColumnType::Integer);
+        assert_eq!(
+            column_type(vec!["1", "2.5", "1.125"]),
+            ColumnType::Float { precision: 3 }
+        );
+        assert_eq!(
+            column_type(vec!["2018-03-01", "2018-03-02T12:00:00Z"]),
+            ColumnType::Date
+        );
+        assert_eq!(column_type(vec!["yes", "N"]), ColumnType::Bool);
+        assert_eq!(column_type(vec!["0", "1"]), ColumnType::Integer);
+        assert_eq!(column_type(vec!["1", "one"]), ColumnType::Text);
+        assert_eq!(column_type(vec!["NaN"]), ColumnType::Text);
+        assert_eq!(column_type(vec![""]), ColumnType::Text);
+    }
+}
@@ -55,283 +55,4584 @@
 //! # da | foobar! | bar #
 //! ######################
 //! ```
-extern crate itertools;
+#[cfg(feature = "chrono")]
+extern crate chrono;
+#[cfg(feature = "csv")]
+extern crate csv;
+#[cfg(feature = "regex")]
+extern crate regex;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "terminal_size")]
+extern crate terminal_size;
+extern crate unicode_width;
+use std::borrow::Cow;
 use std::cmp;
+use std::collections::{BTreeSet, HashMap, HashSet};
+#[cfg(feature = "tty")]
+use std::env;
 use std::fmt;
-use std::iter;
+#[cfg(feature = "tty")]
+use std::io::{self, IsTerminal, Write};
+#[cfg(feature = "tty")]
+use std::mem;
+use std::ops::Range;
 use std::vec;
 
-use self::itertools::join;
+use highlight::Pattern;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+mod balance;
+mod border;
+pub mod cell;
+mod error;
+mod format;
+mod heatmap;
+mod highlight;
+mod infer;
+mod markdown;
+mod optimal;
+pub mod prettytable;
+mod render;
+mod rule;
+mod sanitize;
+#[cfg(feature = "serde")]
+pub mod ser;
+mod sort;
+mod sql;
+mod stat;
+mod style;
+mod truncate;
+mod tsv;
+pub mod typed;
+mod view;
+mod xml;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+pub use border::{BorderChars, RuleChars};
+pub use cell::{Cell, CellBorders, RowBuilder};
+use cell::{CellSettings, Dynamic};
+pub use error::{ConfigError, Error};
+pub use format::{BoolFormat, ByteFormat, Format};
+pub use heatmap::Heatmap;
+pub use infer::ColumnType;
+pub use markdown::MarkdownRenderer;
+pub use render::{PlainRenderer, Renderer, TextRenderer};
+pub use rule::Rule;
+pub use sanitize::ControlChars;
+pub use sort::natural_cmp;
+pub use sql::SqlRenderer;
+pub use stat::Stat;
+pub use style::{Color, Style};
+pub use truncate::Ellipsis;
+pub use tsv::TsvRenderer;
+pub use typed::TypedTable;
+pub use view::TableView;
+pub use xml::XmlRenderer;
+#[cfg(feature = "yaml")]
+pub use yaml::YamlRenderer;
 
 /// Type alias for a row.
-type Row = Vec<String>;
+///
+/// Cells are `Cow`s so string literals don't need to be copied.
+pub type Row<'a> = Vec<Cow<'a, str>>;
 
 /// This macro simplifies `Row` creation
 ///
+/// Values are strings or `Option`s of anything displayable, see
+/// `cell::RowValue`.
+///
 /// # Example
 /// ```
 /// # #[macro_use] extern crate lazytable;
 /// # fn main() {
 /// let row = row!["foo", "bar"];
+/// let port: Option<u16> = None;
+/// let row = row!["foo", Some(8080), port];
+/// assert_eq!(row, ["foo", "8080", ""]);
 /// # }
 /// ```
 #[macro_export]
 macro_rules! row {
-     ($($content:expr), *) => ((vec![$($content.to_owned()), *]));
+     ($($content:expr), *) => ((vec![$($crate::cell::RowValue::into_cow($content)), *]));
 }
 
 /// Width, padding and border strings of a table.
+///
+/// ```
+/// use lazytable::{BorderChars, Table, TableConfig};
+///
+/// let config = TableConfig::default()
+///     .width(40)
+///     .border(BorderChars::unicode())
+///     .frame(true);
+/// let table = Table::new(config);
+/// ```
 pub struct TableConfig<'a> {
     width: usize,
     padding: usize,
-    border: (&'a str, &'a str, &'a str),
+    border: BorderChars<'a>,
+    frame: bool,
+    arrangement: ContentArrangement,
+    wrapping: Wrapping,
+    continuation: Option<Continuation<'a>>,
+    merge_cells: bool,
+    tree_indent: usize,
+    control_chars: ControlChars,
+    sanitized: Option<usize>,
+    outer_rules: bool,
+    center_title: bool,
+    title_rule: Option<&'a str>,
+    title_rule_style: Option<Style>,
+    title_rule_chars: Option<RuleChars<'a>>,
+    separate_footer: bool,
+    footer_rule: Option<&'a str>,
+    show_row_numbers: bool,
+    stack_columns: bool,
+    key_column: Option<usize>,
+    layout: Layout,
+    right_to_left: bool,
+    truncate: Option<Ellipsis>,
+    ellipsis_marker: &'a str,
+    trim_line_ends: bool,
+    line_ending: LineEnding,
+    final_line_ending: bool,
+    pad_last_column: bool,
+    empty: Empty<'a>,
 }
 
 /// Default `TableConfig` with:
 /// * `width: 80`
 /// * `padding: 1`
 /// * `border: |-+`
+/// * `frame: false`
+/// * `arrangement: Dynamic`
+/// * `wrapping: Greedy`
+/// * `continuation: None`
+/// * `merge_cells: false`
+/// * `tree_indent: 4`
+/// * `control_chars: PassThrough`
+/// * `sanitized: None`
+/// * `outer_rules: false`
+/// * `center_title: false`
+/// * `title_rule: None`, the title is underlined like other rules
+/// * `title_rule_style: None`
+/// * `title_rule_chars: None`
+/// * `separate_footer: true`
+/// * `footer_rule: None`, the footer is separated like the title
+/// * `show_row_numbers: false`
+/// * `stack_columns: false`
+/// * `key_column: None`
+/// * `layout: Table`
+/// * `right_to_left: false`
+/// * `truncate: None`, cells are wrapped
+/// * `ellipsis_marker: "…"`
+/// * `trim_line_ends: false`
+/// * `line_ending: Lf`
+/// * `final_line_ending: true`
+/// * `pad_last_column: true`
+/// * `empty: Title`
 impl<'a> Default for TableConfig<'a> {
     fn default() -> TableConfig<'a> {
         TableConfig {
             width: 80,
             padding: 1,
-            border: ("|", "-", "+"),
+            border: BorderChars::default(),
+            frame: false,
+            arrangement: ContentArrangement::default(),
+            wrapping: Wrapping::default(),
+            continuation: None,
+            merge_cells: false,
+            tree_indent: 4,
+            control_chars: ControlChars::default(),
+            sanitized: None,
+            outer_rules: false,
+            center_title: false,
+            title_rule: None,
+            title_rule_style: None,
+            title_rule_chars: None,
+            separate_footer: true,
+            footer_rule: None,
+            show_row_numbers: false,
+            stack_columns: false,
+            key_column: None,
+            layout: Layout::default(),
+            right_to_left: false,
+            truncate: None,
+            ellipsis_marker: "…",
+            trim_line_ends: false,
+            line_ending: LineEnding::default(),
+            final_line_ending: true,
+            pad_last_column: true,
+            empty: Empty::default(),
         }
     }
 }
 
-#[derive(Default)]
-pub struct Table<'a> {
-    title: Option<Row>,
-    rows: Vec<Row>,
-    config: TableConfig<'a>,
-}
+impl<'a> TableConfig<'a> {
+    /// A configuration without lines and padding, columns separated by two
+    /// spaces like the output of `kubectl get`.
+    ///
+    /// ```
+    /// use lazytable::{Table, TableConfig};
+    ///
+    /// let mut table = Table::new(TableConfig::borderless());
+    /// table.set_title(["NAME", "READY", "STATUS"]);
+    /// table.add_row(["web-1", "1/1", "Running"]);
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     "NAME   READY  STATUS \nweb-1  1/1    Running\n"
+    /// );
+    /// ```
+    pub fn borderless() -> TableConfig<'a> {
+        TableConfig::default()
+            .border(BorderChars::borderless())
+            .padding(0)
+    }
 
-impl<'a> Table<'a> {
-    pub fn new(config: TableConfig<'a>) -> Table {
-        Table {
-            title: None,
-            rows: vec![],
-            config: config,
-        }
+    /// A framed configuration with light lines and rounded corners.
+    ///
+    /// ```
+    /// use lazytable::{Table, TableConfig};
+    ///
+    /// let mut table = Table::new(TableConfig::rounded());
+    /// table.set_title(["who", "what"]);
+    /// table.add_row(["bob", "tea"]);
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     "╭─────┬──────╮\n│ who │ what │\n├─────┼──────┤\n│ bob │ tea  │\n╰─────┴──────╯\n"
+    /// );
+    /// ```
+    pub fn rounded() -> TableConfig<'a> {
+        TableConfig::default()
+            .border(BorderChars::rounded())
+            .frame(true)
     }
 
-    /// Creates a table with a default config and `width`.
-    pub fn with_width(width: usize) -> Table<'a> {
-        let mut config = TableConfig::default();
-        config.width = width;
-        Table::new(config)
+    /// Set the total width.
+    pub fn width(mut self, width: usize) -> TableConfig<'a> {
+        self.width = width;
+        self
     }
 
-    /// Set the title row.
-    pub fn set_title(&mut self, title: Row) {
-        self.title = Some(title);
+    /// Set the padding on each side of a cell.
+    pub fn padding(mut self, padding: usize) -> TableConfig<'a> {
+        self.padding = padding;
+        self
     }
 
-    /// Add a row.
-    pub fn add_row(&mut self, row: Row) {
-        self.rows.push(row);
+    /// Set the characters used for separators and frame.
+    pub fn border(mut self, border: BorderChars<'a>) -> TableConfig<'a> {
+        self.border = border;
+        self
     }
 
-    /// Add multiple rows at once.
-    pub fn add_rows(&mut self, rows: &mut Vec<Row>) {
-        self.rows.append(rows);
+    /// Draw a frame around the table.
+    pub fn frame(mut self, frame: bool) -> TableConfig<'a> {
+        self.frame = frame;
+        self
     }
 
-    fn dimensions(&self) -> Vec<usize> {
-        let dimensions = self.title
-            .iter()
-            .chain(self.rows.iter())
-            .map(|x| x.iter().map(|s| s.len()).collect::<Vec<_>>())
-            .fold(vec::Vec::<usize>::new(), |l, r| max_merge(&l, &r));
-        distribute(&dimensions, self.config.width, self.config.padding)
+    /// Set how column widths relate to the configured width.
+    pub fn arrangement(mut self, arrangement: ContentArrangement) -> TableConfig<'a> {
+        self.arrangement = arrangement;
+        self
     }
 
-    fn fmt_row(
-        &self,
-        row: &[String],
-        dimenstions: &[usize],
-        f: &mut fmt::Formatter,
-    ) -> fmt::Result {
-        let expanded = dimenstions
-            .iter()
-            .zip(row.iter())
-            .map(|(dim, cell)| split(cell, *dim))
-            .collect::<Vec<_>>();
-        let height = expanded.iter().map(|x| x.len()).max().unwrap_or(0);
-        for i in 0..height {
-            let row = join(
-                expanded
-                    .iter()
-                    .map(|x| {
-                        x.get(i)
-                            .and_then(|x| Some(x.to_owned()))
-                            .unwrap_or_default()
-                    })
-                    .zip(dimenstions.iter())
-                    .map(|(c, w)| {
-                        format!("{pad}{cell: <width$}{pad}", pad = " ", width = w, cell = c)
-                    }),
-                self.config.border.0,
-            );
-            write!(f, "{}\n", row)?;
+    /// Set how cells are broken into lines.
+    pub fn wrapping(mut self, wrapping: Wrapping) -> TableConfig<'a> {
+        self.wrapping = wrapping;
+        self
+    }
+
+    /// Mark where cells are wrapped.
+    ///
+    /// ```
+    /// use lazytable::{Continuation, Table, TableConfig};
+    ///
+    /// let config = TableConfig::default()
+    ///     .width(14)
+    ///     .continuation(Continuation::Prefix("↪ "));
+    /// let mut table = Table::new(config);
+    /// table.add_row(["ls", "-l /usr/local/bin"]);
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     " ls | -l      \n    | ↪ /usr/ \n    | ↪ local \n    | ↪ /bin  \n"
+    /// );
+    /// ```
+    pub fn continuation(mut self, continuation: Continuation<'a>) -> TableConfig<'a> {
+        self.continuation = Some(continuation);
+        self
+    }
+
+    /// Merge adjacent body cells of a row with the same content into one cell
+    /// spanning their columns, centered.
+    ///
+    /// ```
+    /// use lazytable::{Table, TableConfig};
+    ///
+    /// let mut table = Table::new(TableConfig::default().merge_cells(true));
+    /// table.set_title(["", "mon", "tue", "wed"]);
+    /// table.add_row(["ana", "off", "off", "on"]);
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     "     | mon | tue | wed \n-----+-----+-----+-----\n ana |    off    | on  \n"
+    /// );
+    /// ```
+    pub fn merge_cells(mut self, merge_cells: bool) -> TableConfig<'a> {
+        self.merge_cells = merge_cells;
+        self
+    }
+
+    /// Indent child rows by `tree_indent` columns per level, at least 2.
+    pub fn tree_indent(mut self, tree_indent: usize) -> TableConfig<'a> {
+        self.tree_indent = cmp::max(tree_indent, 2);
+        self
+    }
+
+    /// Set how control characters in cells are handled.
+    ///
+    /// This is applied to title and body cells before measuring.
+    pub fn control_chars(mut self, control_chars: ControlChars) -> TableConfig<'a> {
+        self.control_chars = control_chars;
+        self
+    }
+
+    /// Make cells from untrusted input safe to print.
+    ///
+    /// Escape sequences, control characters and invisible or bidirectional
+    /// formatting characters are removed, other spaces replaced by `' '` and
+    /// cells longer than `max_cell_len` characters cut short with `…`.
+    ///
+    /// Unicode isn't normalized, e.g. `"e\u{301}"` and `"é"` stay different
+    /// and lookalike characters pass. Normalize input to NFC before adding
+    /// it, e.g. with the `unicode-normalization` crate, if that matters.
+    ///
+    /// ```
+    /// use lazytable::{Table, TableConfig};
+    ///
+    /// let mut table = Table::new(TableConfig::default().sanitized(6));
+    /// table.add_row(["\x1b[2Jroot\u{202e}", "x\u{a0}y\r\n"]);
+    /// table.add_row(["a-very-long-name", ""]);
+    /// assert_eq!(table.to_string(), " root   | x y \n a-ver… |     \n");
+    /// ```
+    pub fn sanitized(mut self, max_cell_len: usize) -> TableConfig<'a> {
+        self.sanitized = Some(max_cell_len);
+        self
+    }
+
+    /// Draw a line above and below the table even without a frame.
+    ///
+    /// ```
+    /// use lazytable::{Table, TableConfig};
+    ///
+    /// let mut table = Table::new(TableConfig::default().outer_rules(true));
+    /// table.set_title(["a", "b"]);
+    /// table.add_row(["1", "2"]);
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     "---+---\n a | b \n---+---\n 1 | 2 \n---+---\n"
+    /// );
+    /// ```
+    pub fn outer_rules(mut self, outer_rules: bool) -> TableConfig<'a> {
+        self.outer_rules = outer_rules;
+        self
+    }
+
+    /// Center title cells over their columns.
+    ///
+    /// Body cells keep the alignment of their column.
+    pub fn center_title(mut self, center_title: bool) -> TableConfig<'a> {
+        self.center_title = center_title;
+        self
+    }
+
+    /// Set the horizontal character of the line below the title.
+    ///
+    /// The other lines keep using `BorderChars::horizontal`.
+    pub fn title_rule(mut self, horizontal: &'a str) -> TableConfig<'a> {
+        self.title_rule = Some(horizontal);
+        self
+    }
+
+    /// Set the style of the line below the title.
+    ///
+    /// Like cell styles this is only applied when the table is colored.
+    pub fn title_rule_style(mut self, style: Style) -> TableConfig<'a> {
+        self.title_rule_style = Some(style);
+        self
+    }
+
+    /// Set all characters of the line below the title, e.g. to draw a
+    /// double line.
+    ///
+    /// This takes precedence over `title_rule`.
+    ///
+    /// ```
+    /// use lazytable::{BorderChars, RuleChars, Table, TableConfig};
+    ///
+    /// let config = TableConfig::default()
+    ///     .border(BorderChars::unicode())
+    ///     .frame(true)
+    ///     .title_rule_chars(RuleChars::double());
+    /// let mut table = Table::new(config);
+    /// table.set_title(["a", "b"]);
+    /// table.add_row(["1", "2"]);
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     "┌───┬───┐\n│ a │ b │\n╞═══╪═══╡\n│ 1 │ 2 │\n└───┴───┘\n"
+    /// );
+    /// ```
+    pub fn title_rule_chars(mut self, chars: RuleChars<'a>) -> TableConfig<'a> {
+        self.title_rule_chars = Some(chars);
+        self
+    }
+
+    /// Draw a line between the body rows and the footer.
+    pub fn separate_footer(mut self, separate_footer: bool) -> TableConfig<'a> {
+        self.separate_footer = separate_footer;
+        self
+    }
+
+    /// Set the horizontal character of the line above the footer.
+    pub fn footer_rule(mut self, horizontal: &'a str) -> TableConfig<'a> {
+        self.footer_rule = Some(horizontal);
+        self
+    }
+
+    /// Prepend a column with the 1-based number of each body row.
+    ///
+    /// Rows keep their number when only some of them are rendered. The
+    /// column indices used to configure columns don't include this column.
+    pub fn show_row_numbers(mut self, show_row_numbers: bool) -> TableConfig<'a> {
+        self.show_row_numbers = show_row_numbers;
+        self
+    }
+
+    /// Split tables wider than the width into several tables with some of
+    /// the columns each, stacked on top of each other.
+    ///
+    /// Columns are as wide as their content, a single column too wide for
+    /// the width is wrapped as usual.
+    ///
+    /// ```
+    /// use lazytable::{Table, TableConfig};
+    ///
+    /// let config = TableConfig::default()
+    ///     .width(24)
+    ///     .stack_columns(true)
+    ///     .key_column(0);
+    /// let mut table = Table::new(config);
+    /// table.set_title(["id", "name", "email"]);
+    /// table.add_row(["1", "Ada", "ada@example.org"]);
+    /// print!("{}", table);
+    /// ```
+    ///
+    /// This will output:
+    ///
+    /// ```text
+    ///  id | name
+    /// ----+------
+    ///  1  | Ada
+    ///
+    ///  id | email
+    /// ----+-----------------
+    ///  1  | ada@example.org
+    /// ```
+    pub fn stack_columns(mut self, stack_columns: bool) -> TableConfig<'a> {
+        self.stack_columns = stack_columns;
+        self
+    }
+
+    /// Repeat column `idx` in each table when stacking columns and keep it
+    /// in `Table::render_columns_window`.
+    pub fn key_column(mut self, idx: usize) -> TableConfig<'a> {
+        self.key_column = Some(idx);
+        self
+    }
+
+    /// Set how body rows are laid out.
+    ///
+    /// ```
+    /// use lazytable::{Layout, Table, TableConfig};
+    ///
+    /// let mut table = Table::new(TableConfig::default().layout(Layout::Record));
+    /// table.set_title(["id", "name"]);
+    /// table.add_row(["1", "Ada"]);
+    /// assert_eq!(table.to_string(), "-[ RECORD 1 ]-\n id   | 1     \n name | Ada   \n");
+    /// ```
+    pub fn layout(mut self, layout: Layout) -> TableConfig<'a> {
+        self.layout = layout;
+        self
+    }
+
+    /// Show the first column on the right and the last one on the left.
+    ///
+    /// Only the displayed order changes, column indices still refer to the
+    /// order of the cells in a row. Cells keep their alignment.
+    ///
+    /// ```
+    /// use lazytable::{Table, TableConfig};
+    ///
+    /// let mut table = Table::new(TableConfig::default().right_to_left(true));
+    /// table.add_row(["first", "second"]);
+    /// assert_eq!(table.to_string(), " second | first \n");
+    /// ```
+    pub fn right_to_left(mut self, right_to_left: bool) -> TableConfig<'a> {
+        self.right_to_left = right_to_left;
+        self
+    }
+
+    /// Cut cells wider than their column down to a single line with an
+    /// ellipsis instead of wrapping them.
+    ///
+    /// ```
+    /// use lazytable::{Ellipsis, Table, TableConfig};
+    ///
+    /// let config = TableConfig::default().width(21).truncate(Ellipsis::Middle);
+    /// let mut table = Table::new(config);
+    /// table.add_row(["app", "/home/user/target/debug/app"]);
+    /// assert_eq!(table.to_string(), " app | /home/…ug/app \n");
+    /// ```
+    pub fn truncate(mut self, ellipsis: Ellipsis) -> TableConfig<'a> {
+        self.truncate = Some(ellipsis);
+        self
+    }
+
+    /// Set the marker replacing the cut content of truncated cells.
+    ///
+    /// Use `"..."` where the Unicode ellipsis can't be displayed.
+    pub fn ellipsis_marker(mut self, marker: &'a str) -> TableConfig<'a> {
+        self.ellipsis_marker = marker;
+        self
+    }
+
+    /// Don't end lines with spaces, so the last column isn't padded.
+    ///
+    /// Keeps golden files free of trailing whitespace.
+    ///
+    /// ```
+    /// use lazytable::{Table, TableConfig};
+    ///
+    /// let mut table = Table::new(TableConfig::default().trim_line_ends(true));
+    /// table.set_title(["name", "id"]);
+    /// table.add_row(["a", "1"]);
+    /// assert_eq!(table.to_string(), " name | id\n------+----\n a    | 1\n");
+    /// ```
+    pub fn trim_line_ends(mut self, trim_line_ends: bool) -> TableConfig<'a> {
+        self.trim_line_ends = trim_line_ends;
+        self
+    }
+
+    /// Set the characters ending each line.
+    pub fn line_ending(mut self, line_ending: LineEnding) -> TableConfig<'a> {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// End the last line like the others.
+    ///
+    /// ```
+    /// use lazytable::{LineEnding, Table, TableConfig};
+    ///
+    /// let config = TableConfig::default()
+    ///     .line_ending(LineEnding::CrLf)
+    ///     .final_line_ending(false);
+    /// let mut table = Table::new(config);
+    /// table.add_row(["a"]);
+    /// table.add_row(["b"]);
+    /// assert_eq!(table.to_string(), " a \r\n b ");
+    /// ```
+    pub fn final_line_ending(mut self, final_line_ending: bool) -> TableConfig<'a> {
+        self.final_line_ending = final_line_ending;
+        self
+    }
+
+    /// Pad the last column on the right like the others.
+    ///
+    /// Without padding the last column gets `padding` more columns of the
+    /// width and without a frame its cells aren't filled up with spaces.
+    ///
+    /// ```
+    /// use lazytable::{Table, TableConfig};
+    ///
+    /// let mut table = Table::new(TableConfig::default().width(9).pad_last_column(false));
+    /// table.add_row(["a", "bcde"]);
+    /// table.add_row(["a", "b"]);
+    /// assert_eq!(table.to_string(), " a | bcde\n a | b\n");
+    /// ```
+    pub fn pad_last_column(mut self, pad_last_column: bool) -> TableConfig<'a> {
+        self.pad_last_column = pad_last_column;
+        self
+    }
+
+    /// What to render if there are no body rows.
+    ///
+    /// ```
+    /// use lazytable::{Empty, Table, TableConfig};
+    ///
+    /// let config = TableConfig::default().empty(Empty::Placeholder("(no data)"));
+    /// let mut table = Table::new(config);
+    /// table.set_title(["who", "what"]);
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     " who | what \n-----+------\n (no data)  \n"
+    /// );
+    /// ```
+    pub fn empty(mut self, empty: Empty<'a>) -> TableConfig<'a> {
+        self.empty = empty;
+        self
+    }
+
+    /// Check that a table of `column_count` columns can be rendered as
+    /// configured.
+    ///
+    /// Tables render anyway, too narrow ones by leaving out columns.
+    ///
+    /// ```
+    /// use lazytable::TableConfig;
+    ///
+    /// let config = TableConfig::default().width(10);
+    /// assert!(config.validate(2).is_ok());
+    /// let err = config.validate(4).unwrap_err();
+    /// assert_eq!(err.to_string(), "width 10 cannot fit 4 columns with padding 1");
+    /// ```
+    pub fn validate(&self, column_count: usize) -> Result<(), ConfigError> {
+        if let Some(idx) = self.key_column.filter(|&idx| idx >= column_count) {
+            return Err(ConfigError::KeyColumn {
+                idx,
+                columns: column_count,
+            });
+        }
+        let columns = column_count + usize::from(self.show_row_numbers);
+        if columns == 0 || self.arrangement == ContentArrangement::Disabled {
+            return Ok(());
+        }
+        // One character per column with padding, separators and frame.
+        let separator = self.separator_width();
+        let mut needed = columns * (2 * self.padding + 1 + separator) - separator;
+        needed += self.frame_width();
+        if !self.pad_last_column {
+            needed -= self.padding;
+        }
+        if needed > self.width {
+            return Err(ConfigError::TooNarrow {
+                width: self.width,
+                columns,
+                padding: self.padding,
+            });
         }
         Ok(())
     }
+}
 
-    fn fmt_seperator(&self, dimensions: &[usize], f: &mut fmt::Formatter) -> fmt::Result {
-        let row = join(
-            dimensions.iter().map(|dim| {
-                iter::repeat(self.config.border.1.to_string())
-                    .take(dim + self.config.padding * 2)
-                    .collect::<String>()
-            }),
-            self.config.border.2,
-        );
-        write!(f, "{}\n", row)
+impl<'a> TableConfig<'a> {
+    /// The width of the separator between columns.
+    fn separator_width(&self) -> usize {
+        display_width(self.border.vertical)
+    }
+
+    /// The width of the left and right frame together.
+    fn frame_width(&self) -> usize {
+        if self.frame {
+            2 * self.separator_width()
+        } else {
+            0
+        }
+    }
+
+    /// The byte ranges of `cell` wrapped into lines of at most `w` columns,
+    /// leaving room for the continuation marker.
+    ///
+    /// Lines of multi-line cells are wrapped one by one.
+    fn split(&self, cell: &str, w: usize) -> Vec<Range<usize>> {
+        if !cell.contains('\n') {
+            return self.split_line(cell, w);
+        }
+        let mut lines = vec![];
+        let mut offset = 0;
+        for line in cell.split('\n') {
+            let trimmed = line.trim_end_matches('\r');
+            match self.split_line(trimmed, w) {
+                ref wrapped if wrapped.is_empty() => lines.push(offset..offset + trimmed.len()),
+                wrapped => lines.extend(wrapped.into_iter().map(|r| shift(r, offset))),
+            }
+            offset += line.len() + 1;
+        }
+        lines
+    }
+
+    /// Like `split` but keep the indentation of every line, for the lines
+    /// of nested tables.
+    fn split_indented(&self, cell: &str, w: usize) -> Vec<Range<usize>> {
+        let mut lines = vec![];
+        let mut offset = 0;
+        for line in cell.split('\n') {
+            let trimmed = line.trim_end_matches('\r');
+            let indent = trimmed.len() - trimmed.trim_start().len();
+            let wrapped = self.split_prefixed(trimmed, indent, w);
+            lines.extend(wrapped.into_iter().map(|r| shift(r, offset)));
+            offset += line.len() + 1;
+        }
+        lines
+    }
+
+    /// Wrap `cell` keeping its first `prefix` bytes in front of the first
+    /// line.
+    fn split_prefixed(&self, cell: &str, prefix: usize, w: usize) -> Vec<Range<usize>> {
+        let (head, rest) = cell.split_at(prefix);
+        let w = cmp::max(w.saturating_sub(display_width(head)), 1);
+        let mut lines: Vec<_> = self
+            .split(rest, w)
+            .into_iter()
+            .map(|r| shift(r, prefix))
+            .collect();
+        match lines.first_mut() {
+            Some(first) => first.start = 0,
+            None => lines.push(0..head.trim_end().len()),
+        }
+        lines
+    }
+
+    fn split_line(&self, line: &str, w: usize) -> Vec<Range<usize>> {
+        // Styled lines like those of colored nested tables fit as long as
+        // their text does.
+        if line.contains('\x1b') && display_width(line) <= w {
+            return vec![Range {
+                start: 0,
+                end: line.len(),
+            }];
+        }
+        self.wrapping.split(line, self.line_width(line, w))
+    }
+
+    /// The width of the lines of `cell` in a column `w` wide.
+    fn line_width(&self, cell: &str, w: usize) -> usize {
+        match self.continuation {
+            Some(continuation) if display_width(cell) > w => {
+                let w = w.saturating_sub(display_width(continuation.marker()));
+                cmp::max(w, 1)
+            }
+            _ => w,
+        }
     }
 }
 
-impl<'a> fmt::Display for Table<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let dimensions = self.dimensions();
-        if let Some(ref title) = self.title {
-            self.fmt_row(title, &dimensions, f)?;
-            self.fmt_seperator(&dimensions, f)?;
+/// How columns are fitted into the configured width.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ContentArrangement {
+    /// Ignore the width, columns are as wide as their content.
+    Disabled,
+    /// Shrink and wrap columns to fit the width.
+    ///
+    /// Columns get at least one character, if that's still too wide the
+    /// last columns are left out.
+    #[default]
+    Dynamic,
+    /// Like `Dynamic` but stretch columns to fill the whole width.
+    DynamicFullWidth,
+    /// Like `Dynamic` but choose the column widths giving the fewest lines
+    /// instead of sharing the width fairly.
+    ///
+    /// This wraps cells repeatedly to compare widths, it's slower than the
+    /// others for large tables.
+    MinimizeHeight,
+}
+
+/// How cells wider than their column are broken into lines.
+///
+/// Both break lines at spaces and words too wide for a line anywhere.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Wrapping {
+    /// Fill each line as far as possible.
+    #[default]
+    Greedy,
+    /// Keep the free space at the end of lines even, like in typeset
+    /// paragraphs. Nicer for prose, but slower.
+    ///
+    /// ```
+    /// use lazytable::{Table, TableConfig, Wrapping};
+    ///
+    /// let mut table = Table::new(TableConfig::default().width(9));
+    /// table.add_row(["aaa bb cc dddddd"]);
+    /// assert_eq!(table.to_string(), " aaa bb  \n cc      \n dddddd  \n");
+    /// table = Table::new(TableConfig::default().width(9).wrapping(Wrapping::Optimal));
+    /// table.add_row(["aaa bb cc dddddd"]);
+    /// assert_eq!(table.to_string(), " aaa     \n bb cc   \n dddddd  \n");
+    /// ```
+    Optimal,
+}
+
+impl Wrapping {
+    /// The byte ranges of `cell` wrapped into lines of at most `w` columns.
+    fn split(self, cell: &str, w: usize) -> Vec<Range<usize>> {
+        match self {
+            Wrapping::Greedy => split(cell, w),
+            Wrapping::Optimal => optimal::split(cell, w),
         }
-        for row in &self.rows {
-            self.fmt_row(row, &dimensions, f)?;
+    }
+}
+
+/// A marker for wrapped cells, see `TableConfig::continuation`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Continuation<'a> {
+    /// Appended to lines which are continued, like `\\` in shell scripts.
+    Suffix(&'a str),
+    /// Prepended to lines continuing the previous one, like `↪`.
+    Prefix(&'a str),
+}
+
+impl<'a> Continuation<'a> {
+    fn marker(self) -> &'a str {
+        match self {
+            Continuation::Suffix(marker) | Continuation::Prefix(marker) => marker,
         }
-        Ok(())
     }
 }
 
-fn split(cell: &str, w: usize) -> Vec<String> {
-    let mut lines = vec![];
-    let max = cell.len();
-    let mut from = 0;
-    while from < max {
-        let till = cmp::min(from + w, max);
-        let i = if till < max {
-            match cell[from..till].rfind(' ') {
-                Some(i) => i + 1,
-                None => w,
-            }
-        } else {
-            w
-        };
-        let till = cmp::min(from + i, max);
-        lines.push(cell[from..till].trim().to_owned());
-        from += i;
+/// How body rows are laid out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+    /// One line per row and one column per cell.
+    #[default]
+    Table,
+    /// A block of `header | value` lines per row below a `-[ RECORD n ]-`
+    /// line, like the expanded output of `psql`.
+    ///
+    /// Handy for tables with many columns on a narrow terminal.
+    Record,
+}
+
+/// What is rendered for a table without body rows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Empty<'a> {
+    /// Nothing at all, not even the title or frame.
+    Nothing,
+    /// The title, footer and frame.
+    #[default]
+    Title,
+    /// Like `Title` with a line spanning all columns in place of the rows.
+    Placeholder(&'a str),
+}
+
+/// The characters ending a line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+}
+
+impl LineEnding {
+    /// The line ending as a string.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
     }
-    lines
 }
 
-fn flying(col_width: usize, cols: usize, width: usize, padding: usize) -> usize {
-    let space = cols * 2 * padding + (cols - 1);
-    let fair = (width - space) / cols;
-    cmp::min(col_width, fair)
+/// Direction of a sort key.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Order {
+    #[default]
+    Ascending,
+    Descending,
 }
 
-fn max_merge(left: &[usize], right: &[usize]) -> Vec<usize> {
-    let mut merged = left.iter()
-        .zip(right.iter())
-        .map(|(l, r)| *cmp::max(l, r))
-        .collect::<Vec<_>>();
-    let both = merged.len();
-    merged.append(&mut left.iter().skip(both).cloned().collect::<Vec<_>>());
-    merged.append(&mut right.iter().skip(both).cloned().collect::<Vec<_>>());
-    merged
+/// Horizontal alignment of cell content.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Right,
+    Center,
 }
 
-fn distribute(dimensions: &[usize], width: usize, padding: usize) -> Vec<usize> {
-    let mut indexed = dimensions.iter().cloned().enumerate().collect::<Vec<_>>();
-    indexed.sort_by(|a, b| a.1.cmp(&b.1));
-    let mut width = width;
-    let mut cols = dimensions.len();
-    let mut distributed = indexed
-        .iter()
-        .map(|&(i, x)| {
-            let size = flying(x, cols, width, padding);
-            cols -= 1;
-            if cols > 0 {
-                width -= size + 2 * padding + 1;
-            }
-            (i, size)
-        })
-        .collect::<Vec<_>>();
-    distributed.sort_by(|a, b| a.0.cmp(&b.0));
-    distributed.iter().map(|&(_, x)| x).collect()
+/// Settings of a single column.
+#[derive(Default)]
+struct Column<'a> {
+    format: Option<Format<'a>>,
+    alignment: Option<Alignment>,
+    width: Option<usize>,
+    ellipsis_marker: Option<&'a str>,
+    heatmap: Option<Heatmap>,
+    wrapper: Option<WrapperFn<'a>>,
+    formatter: Option<FormatterFn<'a>>,
+    repeat_marker: Option<&'a str>,
 }
 
-#[cfg(test)]
-mod tests {
-    macro_rules! ownv {
-        ($($s:expr),*) => { vec!($($s.to_owned()), *) }
+/// A line between body rows that isn't part of the data.
+enum Divider<'a> {
+    /// A blank line spanning all columns.
+    Spacer,
+    /// A single cell spanning all columns.
+    Section(Cow<'a, str>),
+}
+
+/// Callback transforming a cell for display.
+type FormatterFn<'a> = Box<dyn Fn(&str) -> String + Send + Sync + 'a>;
+
+/// Callback wrapping a cell into lines of at most the given width.
+type WrapperFn<'a> = Box<dyn Fn(&str, usize) -> Vec<String> + Send + Sync + 'a>;
+
+/// Callback choosing the style of a body row from its index and content.
+type RowStyleFn<'a> = Box<dyn Fn(usize, &[Cow<str>]) -> Option<Style> + Send + Sync + 'a>;
+
+/// Key value pairs of a map, see `Table::from_ordered_maps`.
+type Pairs<'a> = Vec<(Cow<'a, str>, Cow<'a, str>)>;
+
+/// Tables are `Send` and `Sync`, callbacks like those of
+/// `set_row_style_fn` and `set_column_formatter` have to be as well.
+#[derive(Default)]
+pub struct Table<'a> {
+    title: Option<Row<'a>>,
+    rows: Vec<Row<'a>>,
+    footer: Option<Row<'a>>,
+    /// Tags of the body rows, may be shorter than `rows`.
+    tags: Vec<Vec<&'a str>>,
+    /// Settings of single body cells, may be shorter than `rows`.
+    cell_settings: Vec<Vec<CellSettings<'a>>>,
+    /// Tree depths of the body rows, may be shorter than `rows`.
+    depths: Vec<usize>,
+    tag_styles: Vec<(&'a str, Style)>,
+    /// Dividers and the index of the body row they precede.
+    dividers: Vec<(usize, Divider<'a>)>,
+    section_style: Option<Style>,
+    none_placeholder: &'a str,
+    config: TableConfig<'a>,
+    columns: Vec<Column<'a>>,
+    row_style: Option<RowStyleFn<'a>>,
+    rules: Vec<Rule<'a>>,
+    highlights: Vec<(Pattern<'a>, Style)>,
+    color: Option<bool>,
+    #[cfg(feature = "terminal_size")]
+    fit_terminal: bool,
+    prepared: Option<Prepared>,
+    /// Widths of the displayed columns, see `freeze_layout`.
+    frozen: Option<Vec<(Option<usize>, usize)>>,
+}
+
+/// A line of a wrapped cell.
+struct Line<'c> {
+    text: Cow<'c, str>,
+    /// The byte offset of the line in the cell, `None` if a column wrapper
+    /// made it up.
+    start: Option<usize>,
+}
+
+impl<'c> Line<'c> {
+    /// The line `range` of `cell`.
+    fn of(cell: &'c str, range: Range<usize>) -> Line<'c> {
+        Line {
+            start: Some(range.start),
+            text: Cow::Borrowed(&cell[range]),
+        }
     }
+}
 
-    use super::*;
-    #[test]
-    fn it_works() {
+/// Body rows wrapped for fixed column widths, see `Table::prepare`.
+struct Prepared {
+    width: usize,
+    dimensions: Vec<usize>,
+    /// The formatted cells of each row with the byte ranges of their lines.
+    lines: Vec<Vec<(String, Vec<Range<usize>>)>>,
+}
+
+impl Prepared {
+    /// The wrapped cells of the formatted body row `i`, `None` if a cell
+    /// changed since it was prepared.
+    fn wrap<'c, S: AsRef<str>>(&self, i: usize, row: &'c [S]) -> Option<Vec<Vec<Line<'c>>>> {
+        let prepared = &self.lines[i];
+        if row.len() != prepared.len() {
+            return None;
+        }
+        row.iter()
+            .zip(prepared)
+            .map(|(cell, (content, lines))| {
+                let cell = cell.as_ref();
+                if cell != content {
+                    return None;
+                }
+                lines
+                    .iter()
+                    .map(|r| cell.get(r.clone()).map(|_| Line::of(cell, r.clone())))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl<'a> Table<'a> {
+    pub fn new(config: TableConfig<'a>) -> Table<'a> {
+        Table {
+            title: None,
+            rows: vec![],
+            footer: None,
+            tags: vec![],
+            cell_settings: vec![],
+            depths: vec![],
+            tag_styles: vec![],
+            dividers: vec![],
+            section_style: None,
+            none_placeholder: "",
+            config,
+            columns: vec![],
+            row_style: None,
+            rules: vec![],
+            highlights: vec![],
+            color: None,
+            #[cfg(feature = "terminal_size")]
+            fit_terminal: false,
+            prepared: None,
+            frozen: None,
+        }
+    }
+
+    /// Creates a table for untrusted input, see `TableConfig::sanitized`.
+    ///
+    /// Cells are cut short after 256 characters.
+    pub fn sanitized() -> Table<'a> {
+        Table::new(TableConfig::default().sanitized(256))
+    }
+
+    /// Creates a table styled like a prettytable format.
+    pub fn from_format(format: &'a prettytable::TableFormat) -> Table<'a> {
+        Table::new(format.into())
+    }
+
+    /// Parses the first GitHub style pipe table in `text`.
+    ///
+    /// Alignment markers of the delimiter row set the column alignments.
+    /// Fails with `Error::Parse` if there is no table with a delimiter row.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let text = "\
+    /// | name | size |
+    /// |------|-----:|
+    /// | a.rs | 120  |
+    /// | b.rs | 3    |
+    /// ";
+    /// let table = Table::from_markdown(text).unwrap();
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     " name | size \n------+------\n a.rs |  120 \n b.rs |    3 \n"
+    /// );
+    /// ```
+    pub fn from_markdown(text: &'a str) -> Result<Table<'a>, Error> {
+        let (title, alignments, rows) = markdown::parse(text)
+            .ok_or_else(|| Error::Parse("no table with a delimiter row".to_owned()))?;
         let mut table = Table::default();
-        table.set_title(ownv!["who", "what"]);
-        table.add_rows(&mut vec![ownv!["a", "b"], ownv!["c", "d"]]);
-        table.add_row(ownv!["foobar", "foobar2000"]);
-        assert_eq!(table.dimensions(), vec![6, 10]);
-        let out = format!("{}", table);
-        let should = "\
-# who    | what       #
-#--------+------------#
-# a      | b          #
-# c      | d          #
-# foobar | foobar2000 #
-"
-            .replace("#", "");
-        assert_eq!(out, should);
+        table.set_title(title);
+        table.extend_rows(rows);
+        for (idx, alignment) in alignments.into_iter().enumerate() {
+            if let Some(alignment) = alignment {
+                table.set_column_alignment(idx, alignment);
+            }
+        }
+        Ok(table)
     }
 
-    #[test]
-    fn test_max_merge() {
-        let l = vec![1, 2, 3];
-        let r = vec![2, 0, 3, 4];
-        assert_eq!(max_merge(&l, &r), vec![2, 2, 3, 4]);
-        let l = vec![];
-        let r = vec![2, 0, 3, 4];
-        assert_eq!(max_merge(&l, &r), r);
+    /// Creates a table from columns of a title and their cells.
+    ///
+    /// Fails with `Error::ColumnLength` unless all columns have as many
+    /// cells as the first one.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let whos = vec!["alice", "bob"];
+    /// let whats = vec!["tea", "coffee"];
+    /// let table = Table::from_columns(vec![("who", whos), ("what", whats)]).unwrap();
+    /// assert_eq!(table.rows()[1], ["bob", "coffee"]);
+    /// assert!(Table::from_columns(vec![("who", vec!["alice"]), ("what", vec![])]).is_err());
+    /// ```
+    pub fn from_columns<I, N, C, S>(columns: I) -> Result<Table<'a>, Error>
+    where
+        I: IntoIterator<Item = (N, C)>,
+        N: Into<Cow<'a, str>>,
+        C: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        let mut title = vec![];
+        let mut cells: Vec<vec::IntoIter<Cow<'a, str>>> = vec![];
+        for (idx, (name, column)) in columns.into_iter().enumerate() {
+            let column: Row<'a> = column.into_iter().map(Into::into).collect();
+            if let Some(first) = cells.first() {
+                if column.len() != first.len() {
+                    return Err(Error::ColumnLength {
+                        idx,
+                        len: column.len(),
+                        expected: first.len(),
+                    });
+                }
+            }
+            title.push(name.into());
+            cells.push(column.into_iter());
+        }
+        let height = cells.first().map_or(0, |c| c.len());
+        let mut table = Table::default();
+        table.set_title(title);
+        table.extend_rows(
+            (0..height).map(|_| cells.iter_mut().filter_map(Iterator::next).collect()),
+        );
+        Ok(table)
     }
 
-    #[test]
-    fn test_split() {
-        let cell = "foobar2000 foo";
-        assert_eq!(split(cell, 12), ownv!("foobar2000", "foo"));
-        let cell = "foobar2000    foo";
-        assert_eq!(split(cell, 12), ownv!("foobar2000", "foo"));
-        let cell = "foobar2000    foobar2000";
-        assert_eq!(split(cell, 12), ownv!("foobar2000", "foobar2000"));
-        let cell = "foobar2000     foobar2000";
-        assert_eq!(split(cell, 12), ownv!("foobar2000", "", "foobar2000"));
+    /// Creates a table with a row per map and a column per key of any map.
+    ///
+    /// Columns are sorted by their keys, cells of keys missing in a map are
+    /// `Cell::none`.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use lazytable::Table;
+    ///
+    /// let alice: HashMap<_, _> = vec![("who".to_owned(), "alice".to_owned())].into_iter().collect();
+    /// let bob: HashMap<_, _> = vec![
+    ///     ("who".to_owned(), "bob".to_owned()),
+    ///     ("age".to_owned(), "42".to_owned()),
+    /// ]
+    /// .into_iter()
+    /// .collect();
+    /// let mut table = Table::from_maps(vec![alice, bob]);
+    /// table.set_none_placeholder("-");
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     " age | who   \n-----+-------\n -   | alice \n 42  | bob   \n"
+    /// );
+    /// ```
+    pub fn from_maps<I: IntoIterator<Item = HashMap<String, String>>>(maps: I) -> Table<'a> {
+        let rows = Table::pairs(maps);
+        let keys: BTreeSet<&Cow<'a, str>> = rows.iter().flatten().map(|(k, _)| k).collect();
+        let columns = keys.into_iter().cloned().collect();
+        Table::from_pairs(columns, rows)
     }
 
-    #[test]
-    fn test_distribute() {
-        let dims = vec![10, 5, 20, 15];
-        let dis = distribute(&dims, 40, 0);
-        assert_eq!(dis, vec![10, 5, 11, 11]);
+    /// Like `from_maps` for maps with an order like `BTreeMap`, `IndexMap`
+    /// or vectors of pairs, columns are ordered by the first map with their
+    /// key.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let table = Table::from_ordered_maps(vec![
+    ///     vec![("who", "alice"), ("age", "42")],
+    ///     vec![("who", "bob"), ("city", "Oslo")],
+    /// ]);
+    /// assert_eq!(table.title().unwrap(), ["who", "age", "city"]);
+    /// assert_eq!(table.rows()[1], ["bob", "", "Oslo"]);
+    /// ```
+    pub fn from_ordered_maps<I, M, K, V>(maps: I) -> Table<'a>
+    where
+        I: IntoIterator<Item = M>,
+        M: IntoIterator<Item = (K, V)>,
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        let rows = Table::pairs(maps);
+        let mut columns: Vec<Cow<'a, str>> = vec![];
+        for (key, _) in rows.iter().flatten() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+        Table::from_pairs(columns, rows)
     }
-    #[test]
-    fn test_wrapping() {
-        let mut table = Table::with_width(20);
-        table.add_row(ownv!["da", "foobar foobar", "bar"]);
-        table.add_row(ownv!["da", "foobar!", "bar"]);
-        let out = format!("{}", table);
-        let should = "\
-# da | foobar  | bar #
-#    | foobar  |     #
-# da | foobar! | bar #
-"
-            .replace("#", "");
-        assert_eq!(out, should);
+
+    /// Like `from_ordered_maps` with the keys `columns` in this order, other
+    /// keys are left out.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use lazytable::Table;
+    ///
+    /// let alice: HashMap<_, _> = vec![("who", "alice"), ("age", "42")].into_iter().collect();
+    /// let table = Table::from_maps_with_columns(vec![alice], vec!["age", "who", "city"]);
+    /// assert_eq!(table.title().unwrap(), ["age", "who", "city"]);
+    /// assert_eq!(table.rows()[0], ["42", "alice", ""]);
+    /// ```
+    pub fn from_maps_with_columns<I, M, K, V, C, N>(maps: I, columns: C) -> Table<'a>
+    where
+        I: IntoIterator<Item = M>,
+        M: IntoIterator<Item = (K, V)>,
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+        C: IntoIterator<Item = N>,
+        N: Into<Cow<'a, str>>,
+    {
+        let columns = columns.into_iter().map(Into::into).collect();
+        Table::from_pairs(columns, Table::pairs(maps))
+    }
+
+    /// The key value pairs of `maps`.
+    fn pairs<I, M, K, V>(maps: I) -> Vec<Pairs<'a>>
+    where
+        I: IntoIterator<Item = M>,
+        M: IntoIterator<Item = (K, V)>,
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        maps.into_iter()
+            .map(|m| m.into_iter().map(|(k, v)| (k.into(), v.into())).collect())
+            .collect()
     }
 
+    /// A table titled `columns` with a row per key value pairs in `rows`.
+    fn from_pairs(columns: Row<'a>, rows: Vec<Pairs<'a>>) -> Table<'a> {
+        let mut table = Table::default();
+        for row in rows {
+            table.add_cells(
+                columns
+                    .iter()
+                    .map(|c| match row.iter().find(|(k, _)| k == c) {
+                        Some((_, value)) => Cell::new(value.clone()),
+                        None => Cell::none(),
+                    }),
+            );
+        }
+        table.set_title(columns);
+        table
+    }
+
+    /// Reads a table from `reader`, titled with its headers if it has
+    /// headers.
+    ///
+    /// ```
+    /// # extern crate csv;
+    /// # extern crate lazytable;
+    /// # fn main() {
+    /// use lazytable::Table;
+    ///
+    /// let mut reader = csv::Reader::from_reader("who,what\nalice,tea\n".as_bytes());
+    /// let table = Table::read_csv(&mut reader).unwrap();
+    /// assert_eq!(table.title().unwrap(), ["who", "what"]);
+    /// assert_eq!(table.rows()[0], ["alice", "tea"]);
+    /// # }
+    /// ```
+    #[cfg(feature = "csv")]
+    pub fn read_csv<R: io::Read>(reader: &mut csv::Reader<R>) -> Result<Table<'a>, Error> {
+        let mut table = Table::default();
+        if reader.has_headers() {
+            table.set_title(RowBuilder::from(reader.headers()?.clone()).build());
+        }
+        for record in reader.records() {
+            table.add_cells(RowBuilder::from(record?));
+        }
+        Ok(table)
+    }
+
+    /// Creates a table with a default config and `width`.
+    pub fn with_width(width: usize) -> Table<'a> {
+        Table::new(TableConfig::default().width(width))
+    }
+
+    /// Format all body cells of column `idx` with `format`.
+    ///
+    /// The title row is never formatted.
+    pub fn set_column_format(&mut self, idx: usize, format: Format<'a>) {
+        self.column_mut(idx).format = Some(format);
+        self.prepared = None;
+    }
+
+    /// Transform the body cells of column `idx` with `formatter` when
+    /// rendering, after its format is applied.
+    ///
+    /// The stored rows stay unchanged, column widths fit the transformed
+    /// cells.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.set_title(["commit", "subject"]);
+    /// table.add_row(["570d7305e2c1f7a4", "Add markers"]);
+    /// table.set_column_formatter(0, |sha: &str| sha.chars().take(7).collect());
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     " commit  | subject     \n---------+-------------\n 570d730 | Add markers \n"
+    /// );
+    /// assert_eq!(table.rows()[0][0], "570d7305e2c1f7a4");
+    /// ```
+    pub fn set_column_formatter<F>(&mut self, idx: usize, formatter: F)
+    where
+        F: Fn(&str) -> String + Send + Sync + 'a,
+    {
+        self.column_mut(idx).formatter = Some(Box::new(formatter));
+        self.prepared = None;
+    }
+
+    /// Show body cells of column `idx` equal to the cell above as `marker`,
+    /// e.g. `""` or `"\""`.
+    ///
+    /// Cells are compared as displayed, after sorting and formatting. Empty
+    /// cells are never replaced.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.set_title(["date", "event"]);
+    /// table.add_row(["05-01", "deploy"]);
+    /// table.add_row(["05-01", "rollback"]);
+    /// table.add_row(["05-02", "deploy"]);
+    /// table.set_column_suppress_repeats(0, "\"");
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     " date  | event    \n-------+----------\n 05-01 | deploy   \n \"     | rollback \n 05-02 | deploy   \n"
+    /// );
+    /// ```
+    pub fn set_column_suppress_repeats(&mut self, idx: usize, marker: &'a str) {
+        self.column_mut(idx).repeat_marker = Some(marker);
+        self.prepared = None;
+    }
+
+    /// Align all cells of column `idx`, overriding the alignment of its
+    /// format.
+    pub fn set_column_alignment(&mut self, idx: usize, alignment: Alignment) {
+        self.column_mut(idx).alignment = Some(alignment);
+    }
+
+    /// Guess the type of each column from its body cells and align or
+    /// format the columns accordingly.
+    ///
+    /// Integers are right aligned, floats formatted with `Format::Float` to
+    /// their most decimals and booleans centered. Columns with an alignment
+    /// or format keep it, later settings override the inferred ones.
+    ///
+    /// ```
+    /// use lazytable::{ColumnType, Table};
+    ///
+    /// let mut table = Table::default();
+    /// table.add_row(vec!["a", "1.5", "10"]);
+    /// table.add_row(vec!["b", "2.25", "7"]);
+    /// assert_eq!(
+    ///     table.infer_column_types(),
+    ///     [
+    ///         ColumnType::Text,
+    ///         ColumnType::Float { precision: 2 },
+    ///         ColumnType::Integer
+    ///     ]
+    /// );
+    /// assert_eq!(table.to_string(), " a | 1.50 | 10 \n b | 2.25 |  7 \n");
+    /// ```
+    pub fn infer_column_types(&mut self) -> Vec<ColumnType> {
+        let types: Vec<ColumnType> = (0..self.column_count())
+            .map(|col| {
+                infer::column_type(
+                    self.rows
+                        .iter()
+                        .enumerate()
+                        .filter(|&(i, _)| !self.is_none(i, col))
+                        .filter_map(|(_, row)| row.get(col).map(|c| c.as_ref())),
+                )
+            })
+            .collect();
+        for (idx, &column_type) in types.iter().enumerate() {
+            if self.configured_alignment(idx).is_some() {
+                continue;
+            }
+            match column_type {
+                ColumnType::Integer => self.set_column_alignment(idx, Alignment::Right),
+                ColumnType::Float { precision } => {
+                    self.set_column_format(idx, Format::Float { precision })
+                }
+                ColumnType::Bool => self.set_column_alignment(idx, Alignment::Center),
+                ColumnType::Date | ColumnType::Text => {}
+            }
+        }
+        types
+    }
+
+    /// Use `width` instead of the content width of column `idx`.
+    ///
+    /// Like other columns it is shrunk if the table doesn't fit its width.
+    pub fn set_column_width(&mut self, idx: usize, width: usize) {
+        self.column_mut(idx).width = Some(width);
+        self.prepared = None;
+    }
+
+    /// Color the numeric body cells of column `idx` by value.
+    ///
+    /// Rules and cell styles take precedence.
+    pub fn set_column_heatmap(&mut self, idx: usize, heatmap: Heatmap) {
+        self.column_mut(idx).heatmap = Some(heatmap);
+    }
+
+    /// Wrap the body cells of column `idx` with `wrapper` instead of the
+    /// configured wrapping.
+    ///
+    /// `wrapper` gets the cell and the width of the column and returns the
+    /// lines, which shouldn't be wider than that. Highlights don't apply to
+    /// cells of this column.
+    ///
+    /// ```
+    /// use lazytable::{Table, TableConfig};
+    ///
+    /// let mut table = Table::new(TableConfig::default().width(16));
+    /// table.set_title(["path"]);
+    /// table.add_row(["/usr/local/share/doc"]);
+    /// table.set_column_wrapper(0, |cell: &str, width: usize| {
+    ///     let mut lines = vec![String::new()];
+    ///     for part in cell.split_inclusive('/') {
+    ///         if lines.last().unwrap().len() + part.len() > width {
+    ///             lines.push(String::new());
+    ///         }
+    ///         lines.last_mut().unwrap().push_str(part);
+    ///     }
+    ///     lines
+    /// });
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     " path           \n----------------\n /usr/local/    \n share/doc      \n"
+    /// );
+    /// ```
+    pub fn set_column_wrapper<F>(&mut self, idx: usize, wrapper: F)
+    where
+        F: Fn(&str, usize) -> Vec<String> + Send + Sync + 'a,
+    {
+        self.column_mut(idx).wrapper = Some(Box::new(wrapper));
+        self.prepared = None;
+    }
+
+    /// Mark truncated cells of column `idx` with `marker` instead of the
+    /// configured ellipsis marker.
+    pub fn set_column_ellipsis_marker(&mut self, idx: usize, marker: &'a str) {
+        self.column_mut(idx).ellipsis_marker = Some(marker);
+    }
+
+    /// Like `set_column_format` with the column given by its title.
+    ///
+    /// # Panics
+    ///
+    /// If there is no column titled `name`.
+    pub fn set_column_format_by_name(&mut self, name: &str, format: Format<'a>) {
+        let idx = self.named_column(name);
+        self.set_column_format(idx, format);
+    }
+
+    /// Like `set_column_alignment` with the column given by its title.
+    ///
+    /// # Panics
+    ///
+    /// If there is no column titled `name`.
+    pub fn set_column_alignment_by_name(&mut self, name: &str, alignment: Alignment) {
+        let idx = self.named_column(name);
+        self.set_column_alignment(idx, alignment);
+    }
+
+    /// Like `set_column_width` with the column given by its title.
+    ///
+    /// # Panics
+    ///
+    /// If there is no column titled `name`.
+    pub fn set_column_width_by_name(&mut self, name: &str, width: usize) {
+        let idx = self.named_column(name);
+        self.set_column_width(idx, width);
+    }
+
+    /// Style whole body rows based on their index and content.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate lazytable;
+    /// # fn main() {
+    /// use lazytable::{Style, Table};
+    ///
+    /// let mut table = Table::default();
+    /// table.add_row(row!["build", "FAILED"]);
+    /// table.set_row_style_fn(|_, row| {
+    ///     if row[1] == "FAILED" {
+    ///         Some(Style::red())
+    ///     } else {
+    ///         None
+    ///     }
+    /// });
+    /// # }
+    /// ```
+    pub fn set_row_style_fn<F>(&mut self, f: F)
+    where
+        F: Fn(usize, &[Cow<str>]) -> Option<Style> + Send + Sync + 'a,
+    {
+        self.row_style = Some(Box::new(f));
+    }
+
+    /// Add a conditional formatting rule.
+    ///
+    /// Rule styles take precedence over the row style.
+    pub fn add_rule(&mut self, rule: Rule<'a>) {
+        self.rules.push(rule);
+    }
+
+    /// Highlight all occurrences of `pattern` in title and body cells.
+    ///
+    /// Highlights are applied after wrapping, so they don't change column
+    /// widths. Like other styles they need a colored table.
+    ///
+    /// ```
+    /// use lazytable::{Style, Table};
+    ///
+    /// let mut table = Table::default();
+    /// table.add_row(["needle in a haystack"]);
+    /// table.highlight("needle", Style::yellow().bold());
+    /// ```
+    pub fn highlight(&mut self, pattern: &'a str, style: Style) {
+        self.highlights.push((Pattern::Text(pattern), style));
+    }
+
+    /// Highlight all matches of `regex` in title and body cells.
+    #[cfg(feature = "regex")]
+    pub fn highlight_regex(&mut self, regex: regex::Regex, style: Style) {
+        self.highlights.push((Pattern::Regex(regex), style));
+    }
+
+    /// Always (`true`) or never (`false`) emit escape sequences for styles.
+    ///
+    /// By default styles are only rendered if stdout is a terminal and
+    /// `NO_COLOR` isn't set. Without the `tty` feature styles are only
+    /// rendered if forced.
+    pub fn force_color(&mut self, color: bool) {
+        self.color = Some(color);
+    }
+
+    /// Whether styles are rendered.
+    pub fn colored(&self) -> bool {
+        #[cfg(feature = "tty")]
+        {
+            self.color.unwrap_or_else(|| {
+                env::var_os("NO_COLOR").unwrap_or_default().is_empty() && io::stdout().is_terminal()
+            })
+        }
+        #[cfg(not(feature = "tty"))]
+        {
+            self.color.unwrap_or(false)
+        }
+    }
+
+    /// Print to stdout, styled if stdout is a terminal or `force_color` is
+    /// set.
+    ///
+    /// The table is fitted into the terminal like with `fit_terminal`.
+    /// Colors set with `force_color` are ignored. Write errors are returned, e.g. when a pipe is closed early.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.add_row(["a", "b"]);
+    /// table.print_tty(false).unwrap();
+    /// ```
+    #[cfg(feature = "tty")]
+    pub fn print_tty(&mut self, force_color: bool) -> io::Result<()> {
+        let color = self.color.take();
+        if force_color {
+            self.color = Some(true);
+        }
+        let fit_terminal = mem::replace(&mut self.fit_terminal, true);
+        let out = self.render_to_string();
+        self.color = color;
+        self.fit_terminal = fit_terminal;
+        io::stdout().lock().write_all(out.as_bytes())
+    }
+
+    /// Use the width of the terminal instead of the configured width.
+    ///
+    /// The terminal is queried on every render so tables adapt when the
+    /// terminal is resized. If stdout is no terminal the configured width is
+    /// used. Requires the `terminal_size` feature.
+    #[cfg(feature = "terminal_size")]
+    pub fn fit_terminal(&mut self) {
+        self.fit_terminal = true;
+    }
+
+    /// Keep the current column widths, rows added later are wrapped to them.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.add_row(["a", "b"]);
+    /// table.freeze_layout();
+    /// table.add_row(["cc", "d"]);
+    /// assert_eq!(table.to_string(), " a | b \n c | d \n c |   \n");
+    /// table.invalidate_layout();
+    /// assert_eq!(table.to_string(), " a  | b \n cc | d \n");
+    /// ```
+    pub fn freeze_layout(&mut self) {
+        let widths = self.column_widths();
+        self.freeze_layout_with(&widths);
+    }
+
+    /// Keep the column widths at `widths`, e.g. the `column_widths` of a
+    /// related table.
+    ///
+    /// Columns without a width are as wide as their content.
+    pub fn freeze_layout_with(&mut self, widths: &[usize]) {
+        let columns = self.display_columns(None);
+        self.frozen = Some(columns.into_iter().zip(widths.iter().cloned()).collect());
+        self.prepared = None;
+    }
+
+    /// Compute column widths again, undoing `freeze_layout`.
+    pub fn invalidate_layout(&mut self) {
+        self.frozen = None;
+        self.prepared = None;
+    }
+
+    /// Compute column widths and wrap all rows now instead of on every render.
+    ///
+    /// Speeds up tables that are rendered repeatedly, e.g. live dashboards.
+    /// Changing the table discards the prepared layout, as does a different
+    /// terminal width with `fit_terminal`. Cells formatted differently since,
+    /// e.g. by a column formatter, are wrapped again. Tables with columns
+    /// formatted relative to the current time aren't prepared at all.
+    pub fn prepare(&mut self) {
+        let prepared = {
+            let guides = self.tree_guides();
+            let rows = self.display_rows(&guides);
+            let columns = self.display_columns(None);
+            let title = self.display_title(&columns);
+            let footer = self.display_footer(&columns);
+            let dimensions = self.dimensions(title.as_deref(), footer.as_deref(), &rows, &columns);
+            let lines = rows
+                .iter()
+                .enumerate()
+                .map(|(i, row)| {
+                    row.iter()
+                        .zip(&columns)
+                        .zip(&dimensions)
+                        .map(|((cell, column), dim)| {
+                            let lines = self.split_cell(i, cell, *column, &guides[i], *dim);
+                            (cell.to_string(), lines)
+                        })
+                        .collect()
+                })
+                .collect();
+            Prepared {
+                width: self.width(),
+                dimensions,
+                lines,
+            }
+        };
+        self.prepared = Some(prepared);
+    }
+
+    /// The prepared layout if it's still valid.
+    fn prepared(&self) -> Option<&Prepared> {
+        self.prepared
+            .as_ref()
+            .filter(|p| p.width == self.width() && p.lines.len() == self.rows.len())
+            .filter(|_| !self.has_volatile_format())
+    }
+
+    /// The width to fit the table into.
+    fn width(&self) -> usize {
+        #[cfg(feature = "terminal_size")]
+        {
+            if self.fit_terminal {
+                if let Some(width) = terminal_width() {
+                    return width;
+                }
+            }
+        }
+        self.config.width
+    }
+
+    /// Reorder columns, moving the columns `front` to the front.
+    ///
+    /// The remaining columns follow in their current order. Title, rows,
+    /// column settings and rules are all reordered.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.set_title(["who", "what", "when"]);
+    /// table.reorder_columns(&[2, 0]);
+    /// assert_eq!(table.title().unwrap(), ["when", "who", "what"]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `front` contains a column twice.
+    pub fn reorder_columns(&mut self, front: &[usize]) {
+        let count = self
+            .title
+            .iter()
+            .chain(&self.footer)
+            .chain(&self.rows)
+            .map(Vec::len)
+            .chain(front.iter().map(|&c| c + 1))
+            .fold(self.columns.len(), cmp::max);
+        let mut order = front.to_vec();
+        order.extend((0..count).filter(|c| !front.contains(c)));
+        assert_eq!(order.len(), count, "duplicate column in {:?}", order);
+        for row in self
+            .title
+            .iter_mut()
+            .chain(self.footer.iter_mut())
+            .chain(self.rows.iter_mut())
+        {
+            let mut old: Vec<_> = row.drain(..).map(Some).collect();
+            old.resize(count, None);
+            let mut cells: Vec<_> = order.iter().map(|&c| old[c].take()).collect();
+            while let Some(None) = cells.last() {
+                cells.pop();
+            }
+            row.extend(cells.into_iter().map(Option::unwrap_or_default));
+        }
+        for settings in &mut self.cell_settings {
+            let mut old = std::mem::take(settings);
+            old.resize(count, Default::default());
+            *settings = order.iter().map(|&c| std::mem::take(&mut old[c])).collect();
+        }
+        let mut old: Vec<_> = self.columns.drain(..).map(Some).collect();
+        old.resize_with(count, || None);
+        self.columns = order
+            .iter()
+            .map(|&c| old[c].take().unwrap_or_default())
+            .collect();
+        for rule in &mut self.rules {
+            rule.reorder(&order);
+        }
+        for (column, _) in self.frozen.iter_mut().flatten() {
+            if let Some(ref mut c) = *column {
+                *c = order.iter().position(|o| o == c).unwrap_or(*c);
+            }
+        }
+        self.prepared = None;
+    }
+
+    /// Like `reorder_columns` with columns given by their title.
+    ///
+    /// # Panics
+    ///
+    /// If there is no column with one of the titles.
+    pub fn reorder_columns_by_name(&mut self, names: &[&str]) {
+        let order: Vec<usize> = names.iter().map(|name| self.named_column(name)).collect();
+        self.reorder_columns(&order);
+    }
+
+    /// The index of the first column titled `name`.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.set_title(["who", "what", "when"]);
+    /// assert_eq!(table.column_index("when"), Some(2));
+    /// ```
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.title.as_ref()?.iter().position(|c| c == name)
+    }
+
+    fn named_column(&self, name: &str) -> usize {
+        self.column_index(name)
+            .unwrap_or_else(|| panic!("no column named {:?}", name))
+    }
+
+    fn column_mut(&mut self, idx: usize) -> &mut Column<'a> {
+        if self.columns.len() <= idx {
+            self.columns.resize_with(idx + 1, Column::default);
+        }
+        &mut self.columns[idx]
+    }
+
+    /// The alignment of column `idx`.
+    pub fn column_alignment(&self, idx: usize) -> Alignment {
+        self.configured_alignment(idx).unwrap_or_default()
+    }
+
+    /// The alignment set for column `idx` or implied by its format.
+    fn configured_alignment(&self, idx: usize) -> Option<Alignment> {
+        self.columns
+            .get(idx)
+            .and_then(|c| c.alignment.or_else(|| c.format.map(|f| f.alignment())))
+    }
+
+    /// Set the title row, replacing any previous one.
+    ///
+    /// Takes the same arguments as `add_row`.
+    pub fn set_title<I, S>(&mut self, title: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        self.title = Some(title.into_iter().map(Into::into).collect());
+        self.prepared = None;
+    }
+
+    /// Remove the title row.
+    pub fn clear_title(&mut self) {
+        self.title = None;
+        self.prepared = None;
+    }
+
+    /// Set the footer row shown below the body rows, replacing any previous
+    /// one.
+    ///
+    /// Like the title it isn't formatted and it is shown on every page of
+    /// `render_range`. The record layout doesn't show it.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.set_title(["item", "price"]);
+    /// table.add_row(["tea", "3"]);
+    /// table.add_row(["cake", "4"]);
+    /// table.set_footer(["total", "7"]);
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     " item  | price \n-------+-------\n tea   | 3     \n cake  | 4     \n-------+-------\n total | 7     \n"
+    /// );
+    /// ```
+    pub fn set_footer<I, S>(&mut self, footer: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        self.footer = Some(footer.into_iter().map(Into::into).collect());
+        self.prepared = None;
+    }
+
+    /// Remove the footer row.
+    pub fn clear_footer(&mut self) {
+        self.footer = None;
+        self.prepared = None;
+    }
+
+    /// Add a row.
+    ///
+    /// Takes anything iterable over strings, `&str` and `String` can be
+    /// mixed:
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.add_row(["a", "b"]);
+    /// table.add_row(vec![String::from("c"), "d".to_owned()]);
+    /// table.add_row((0..2).map(|i| i.to_string()));
+    /// ```
+    pub fn add_row<I, S>(&mut self, row: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        self.rows.push(row.into_iter().map(Into::into).collect());
+        self.prepared = None;
+    }
+
+    /// Add a row with tags.
+    ///
+    /// Tags aren't rendered, use them to style rows with `set_tag_style` or
+    /// to render a subset of rows with `render_only`:
+    ///
+    /// ```
+    /// use lazytable::{Style, Table};
+    ///
+    /// let mut table = Table::default();
+    /// table.add_row_tagged(["db", "down"], &["error"]);
+    /// table.add_row(["web", "up"]);
+    /// table.set_tag_style("error", Style::red());
+    /// assert_eq!(table.render_only(&["error"]), " db | down \n");
+    /// ```
+    pub fn add_row_tagged<I, S>(&mut self, row: I, tags: &[&'a str])
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        self.add_row(row);
+        self.tags.resize(self.rows.len() - 1, vec![]);
+        self.tags.push(tags.to_vec());
+    }
+
+    /// The tags of body row `i`.
+    pub fn row_tags(&self, i: usize) -> &[&'a str] {
+        self.tags.get(i).map_or(&[], |t| &t[..])
+    }
+
+    /// Add a row below the children of body row `parent` and return its
+    /// index.
+    ///
+    /// The first column of child rows shows tree guides. Sorting and
+    /// filtering rows don't keep children below their parent.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.add_row(["src", "dir"]);
+    /// let render = table.add_child_row(0, ["render", "dir"]);
+    /// table.add_child_row(render, ["mod.rs", "file"]);
+    /// table.add_child_row(0, ["lib.rs", "file"]);
+    /// table.add_row(["Cargo.toml", "file"]);
+    /// assert_eq!(
+    ///     table.to_string(),
+    ///     " src            | dir  \n ├── render     | dir  \n │   └── mod.rs | file \n \
+    ///      └── lib.rs     | file \n Cargo.toml     | file \n"
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If there is no body row `parent`.
+    pub fn add_child_row<I, S>(&mut self, parent: usize, row: I) -> usize
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        assert!(parent < self.rows.len(), "no body row {}", parent);
+        let depth = self.depth(parent) + 1;
+        let mut idx = parent + 1;
+        while idx < self.rows.len() && self.depth(idx) >= depth {
+            idx += 1;
+        }
+        self.rows
+            .insert(idx, row.into_iter().map(Into::into).collect());
+        if idx < self.tags.len() {
+            self.tags.insert(idx, vec![]);
+        }
+        if idx < self.cell_settings.len() {
+            self.cell_settings.insert(idx, vec![]);
+        }
+        self.depths.resize(cmp::max(self.depths.len(), idx), 0);
+        self.depths.insert(idx, depth);
+        for (pos, _) in &mut self.dividers {
+            if *pos >= idx {
+                *pos += 1;
+            }
+        }
+        self.prepared = None;
+        idx
+    }
+
+    /// The tree depth of body row `i`, 0 unless it's a child row.
+    fn depth(&self, i: usize) -> usize {
+        self.depths.get(i).cloned().unwrap_or(0)
+    }
+
+    /// The tree guides in front of the first cell of each body row, empty
+    /// unless it's a child row.
+    fn tree_guides(&self) -> Vec<String> {
+        let indent = self.config.tree_indent;
+        let mut guides = vec![String::new(); self.rows.len()];
+        // Whether a later row of the same tree continues each level.
+        let mut more: Vec<bool> = vec![];
+        for (i, guide) in guides.iter_mut().enumerate().rev() {
+            let depth = self.depth(i);
+            more.resize(depth + 1, false);
+            if depth > 0 {
+                for &more in &more[1..depth] {
+                    guide.push(if more { '│' } else { ' ' });
+                    guide.push_str(&" ".repeat(indent - 1));
+                }
+                guide.push(if more[depth] { '├' } else { '└' });
+                guide.push_str(&"─".repeat(indent - 2));
+                guide.push(' ');
+            }
+            more[depth] = true;
+        }
+        guides
+    }
+
+    /// Add a row of cells, keeping their alignments, styles and dynamic
+    /// content like `Cell::sparkline`.
+    ///
+    /// ```
+    /// use lazytable::{Alignment, Cell, Table};
+    ///
+    /// let mut table = Table::default();
+    /// table.add_row(["total", "sum"]);
+    /// table.add_cells([Cell::new("1"), Cell::new("2").alignment(Alignment::Right)]);
+    /// assert_eq!(table.to_string(), " total | sum \n 1     |   2 \n");
+    /// ```
+    pub fn add_cells<I, C>(&mut self, cells: I)
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<Cell<'a>>,
+    {
+        let (row, settings) = cells
+            .into_iter()
+            .map(|c| {
+                let c = c.into();
+                let settings = CellSettings {
+                    alignment: c.alignment,
+                    style: c.style,
+                    dynamic: c.dynamic,
+                    none: c.none,
+                    borders: c.borders,
+                };
+                (c.content, settings)
+            })
+            .unzip();
+        self.add_row::<Row, _>(row);
+        self.cell_settings.resize(self.rows.len() - 1, vec![]);
+        self.cell_settings.push(settings);
+    }
+
+    /// The settings of the cell in body row `i` and column `col` added with
+    /// `add_cells`.
+    fn cell_settings(&self, i: usize, col: usize) -> Option<&CellSettings<'a>> {
+        self.cell_settings.get(i)?.get(col)
+    }
+
+    /// Draw the column separators of body row `i` with `borders`, e.g. for a
+    /// row reading as one line of text.
+    ///
+    /// ```
+    /// use lazytable::{CellBorders, Table};
+    ///
+    /// let mut table = Table::default();
+    /// table.add_row(["a", "b"]);
+    /// table.add_row(["total", "2"]);
+    /// table.set_row_borders(1, CellBorders::Vertical(":"));
+    /// assert_eq!(table.to_string(), " a     | b \n total : 2 \n");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If there is no body row `i`.
+    pub fn set_row_borders(&mut self, i: usize, borders: CellBorders<'a>) {
+        let len = self.rows[i].len();
+        if self.cell_settings.len() <= i {
+            self.cell_settings.resize(i + 1, vec![]);
+        }
+        let settings = &mut self.cell_settings[i];
+        settings.resize(cmp::max(settings.len(), len), CellSettings::default());
+        for cell in settings {
+            cell.borders = Some(borders);
+        }
+    }
+
+    /// The separator override of the cell in body row `i` and column `col`.
+    fn cell_borders(&self, i: usize, col: usize) -> Option<CellBorders<'a>> {
+        self.cell_settings(i, col)?.borders
+    }
+
+    /// The dynamic content of the cell in body row `i` and column `col`.
+    fn cell_dynamic(&self, i: usize, col: usize) -> Option<&Dynamic<'a>> {
+        self.cell_settings(i, col)?.dynamic.as_ref()
+    }
+
+    /// Whether the cell in body row `i` and column `col` is `Cell::none`.
+    fn is_none(&self, i: usize, col: usize) -> bool {
+        self.cell_settings(i, col).is_some_and(|s| s.none)
+    }
+
+    /// Whether any column has a custom wrapper.
+    fn has_wrapper(&self) -> bool {
+        self.columns.iter().any(|c| c.wrapper.is_some())
+    }
+
+    /// Whether any column has a format whose text changes over time.
+    fn has_volatile_format(&self) -> bool {
+        self.columns
+            .iter()
+            .any(|c| c.format.is_some_and(|f| f.is_volatile()))
+    }
+
+    /// Wrap the cells of body row `i` in the displayed `columns` to their
+    /// width, `guide` is its tree guide.
+    fn wrap_cells<'c, S: AsRef<str>>(
+        &self,
+        i: usize,
+        row: &'c [S],
+        columns: &[Option<usize>],
+        dimensions: &[usize],
+        guide: &str,
+    ) -> Vec<Vec<Line<'c>>> {
+        dimensions
+            .iter()
+            .zip(row.iter())
+            .zip(columns)
+            .map(|((&dim, cell), column)| {
+                let cell = cell.as_ref();
+                match column.and_then(|c| self.columns.get(c)?.wrapper.as_ref()) {
+                    Some(wrapper) => wrapper(cell, self.config.line_width(cell, dim))
+                        .into_iter()
+                        .map(|line| Line {
+                            text: Cow::Owned(line),
+                            start: None,
+                        })
+                        .collect(),
+                    None => self
+                        .split_cell(i, cell, *column, guide, dim)
+                        .into_iter()
+                        .map(|r| Line::of(cell, r))
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// The byte ranges of `cell` of body row `i` in `column` wrapped to `w`
+    /// columns.
+    ///
+    /// Nested tables keep their indentation and the tree guide stays in
+    /// front of the first line, other cells are trimmed.
+    fn split_cell(
+        &self,
+        i: usize,
+        cell: &str,
+        column: Option<usize>,
+        guide: &str,
+        w: usize,
+    ) -> Vec<Range<usize>> {
+        match column {
+            Some(c) if self.cell_dynamic(i, c).is_some() => self.config.split_indented(cell, w),
+            Some(0) if !guide.is_empty() && cell.starts_with(guide) => {
+                self.config.split_prefixed(cell, guide.len(), w)
+            }
+            _ => self.config.split(cell, w),
+        }
+    }
+
+    /// Whether body row `i` has cells with dynamic content.
+    fn has_dynamic(&self, i: usize) -> bool {
+        self.cell_settings
+            .get(i)
+            .is_some_and(|row| row.iter().any(|s| s.dynamic.is_some()))
+    }
+
+    /// Render `Cell::none` cells as `placeholder`, empty by default.
+    ///
+    /// ```
+    /// use lazytable::{Cell, Table};
+    ///
+    /// let mut table = Table::default();
+    /// table.add_cells([Cell::new("a"), Cell::none()]);
+    /// table.set_none_placeholder("—");
+    /// assert_eq!(table.to_string(), " a | — \n");
+    /// ```
+    pub fn set_none_placeholder(&mut self, placeholder: &'a str) {
+        self.none_placeholder = placeholder;
+        self.prepared = None;
+    }
+
+    /// Style rows tagged with `tag`.
+    ///
+    /// A style returned by the `set_row_style_fn` callback takes precedence.
+    /// If a row has several styled tags the one set last wins.
+    pub fn set_tag_style(&mut self, tag: &'a str, style: Style) {
+        self.tag_styles.push((tag, style));
+    }
+
+    /// Add multiple rows at once.
+    pub fn extend_rows<I: IntoIterator<Item = Row<'a>>>(&mut self, rows: I) {
+        self.rows.extend(rows);
+        self.prepared = None;
+    }
+
+    /// Move all rows out of `rows` into the table, leaving it empty.
+    #[deprecated(note = "use `extend_rows`, which doesn't drain the argument")]
+    pub fn add_rows(&mut self, rows: &mut Vec<Row<'a>>) {
+        self.extend_rows(rows.drain(..));
+    }
+
+    /// Add a blank line spanning all columns below the rows added so far.
+    ///
+    /// Use this to separate groups of rows. Spacers aren't rows, they don't
+    /// count for `rows()` or the indices passed to row styles.
+    pub fn add_spacer(&mut self) {
+        self.dividers.push((self.rows.len(), Divider::Spacer));
+    }
+
+    /// Add a row with a single cell spanning all columns below the rows
+    /// added so far.
+    ///
+    /// Use this to break a long table into labeled sections. Like spacers,
+    /// sections aren't rows.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.set_title(["name", "size"]);
+    /// table.add_section("src");
+    /// table.add_row(["lib.rs", "12k"]);
+    /// table.add_section("tests");
+    /// table.add_row(["it.rs", "1k"]);
+    /// ```
+    pub fn add_section<S: Into<Cow<'a, str>>>(&mut self, title: S) {
+        self.dividers
+            .push((self.rows.len(), Divider::Section(title.into())));
+    }
+
+    /// Set the style of section rows.
+    pub fn set_section_style(&mut self, style: Style) {
+        self.section_style = Some(style);
+    }
+
+    /// Replace every body cell of column `idx` with `f` applied to it.
+    ///
+    /// Rows without a cell in column `idx` are skipped.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.add_row(["3f786850e387550fdab836ed7e6dc881de23001b", "ok"]);
+    /// table.map_column(0, |hash| hash[..8].to_owned());
+    /// table.map_column(1, str::to_uppercase);
+    /// assert_eq!(table.rows()[0], ["3f786850", "OK"]);
+    /// ```
+    pub fn map_column<F, S>(&mut self, idx: usize, mut f: F)
+    where
+        F: FnMut(&str) -> S,
+        S: Into<Cow<'a, str>>,
+    {
+        for cell in self.rows.iter_mut().filter_map(|row| row.get_mut(idx)) {
+            *cell = f(cell).into();
+        }
+        self.prepared = None;
+    }
+
+    /// Append a column titled `header` with cells computed from each row.
+    ///
+    /// The cells are computed once for the rows added so far, rows added
+    /// later need to include the column themselves. Shorter rows are padded
+    /// with empty cells so the new cells line up.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.set_title(["hits", "total"]);
+    /// table.add_row(["3", "4"]);
+    /// table.add_computed_column("ratio", |row| {
+    ///     let hits: f64 = row[0].parse().unwrap_or(0.0);
+    ///     let total: f64 = row[1].parse().unwrap_or(1.0);
+    ///     format!("{:.2}", hits / total)
+    /// });
+    /// assert_eq!(table.rows()[0], ["3", "4", "0.75"]);
+    /// ```
+    pub fn add_computed_column<S, F, T>(&mut self, header: S, mut f: F)
+    where
+        S: Into<Cow<'a, str>>,
+        F: FnMut(&[Cow<'a, str>]) -> T,
+        T: Into<Cow<'a, str>>,
+    {
+        let idx = self.column_count();
+        if let Some(ref mut title) = self.title {
+            title.resize(idx, Cow::Borrowed(""));
+            title.push(header.into());
+        }
+        for row in &mut self.rows {
+            let cell = f(row).into();
+            row.resize(idx, Cow::Borrowed(""));
+            row.push(cell);
+        }
+        self.prepared = None;
+    }
+
+    /// A row of `stats` over the body cells of each numeric column.
+    ///
+    /// Columns are numeric if all their non-empty cells are numbers, the
+    /// cells of other columns are empty. Add the row to the body or set it
+    /// as footer.
+    ///
+    /// ```
+    /// use lazytable::{Stat, Table};
+    ///
+    /// let mut table = Table::default();
+    /// table.set_title(["file", "lines"]);
+    /// table.add_row(["a.rs", "120"]);
+    /// table.add_row(["b.rs", "30"]);
+    /// let summary = table.summary_row(&[Stat::Sum, Stat::Max]);
+    /// assert_eq!(summary, ["", "sum 150, max 120"]);
+    /// table.set_footer(summary);
+    /// ```
+    pub fn summary_row(&self, stats: &[Stat]) -> Row<'static> {
+        (0..self.column_count())
+            .map(|col| {
+                let cells = self.rows.iter().filter_map(|row| row.get(col));
+                let summary = stat::summarize(cells.map(|c| c.as_ref()), stats);
+                Cow::Owned(summary.unwrap_or_default())
+            })
+            .collect()
+    }
+
+    /// Sort the body rows by the columns `keys`, the first key taking
+    /// precedence.
+    ///
+    /// The sort is stable, rows comparing equal keep their order. Cells are
+    /// compared as strings, missing cells as empty strings. Spacers and
+    /// sections stay where they are.
+    ///
+    /// ```
+    /// use lazytable::{Order, Table};
+    ///
+    /// let mut table = Table::default();
+    /// table.add_row(["b", "down"]);
+    /// table.add_row(["a", "up"]);
+    /// table.add_row(["c", "down"]);
+    /// table.sort_by_columns(&[(1, Order::Descending), (0, Order::Ascending)]);
+    /// assert_eq!(table.rows()[0], ["a", "up"]);
+    /// assert_eq!(table.rows()[2], ["c", "down"]);
+    /// ```
+    pub fn sort_by_columns(&mut self, keys: &[(usize, Order)]) {
+        self.sort_by_columns_with(keys, |a, b| a.cmp(b));
+    }
+
+    /// Like `sort_by_columns` comparing cells with `compare`.
+    ///
+    /// Use `natural_cmp` to sort numbers by their value:
+    ///
+    /// ```
+    /// use lazytable::{natural_cmp, Order, Table};
+    ///
+    /// let mut table = Table::default();
+    /// table.extend_rows(vec![vec!["item10".into()], vec!["item2".into()]]);
+    /// table.sort_by_columns_with(&[(0, Order::Ascending)], natural_cmp);
+    /// assert_eq!(table.rows()[0], ["item2"]);
+    /// ```
+    pub fn sort_by_columns_with<F>(&mut self, keys: &[(usize, Order)], compare: F)
+    where
+        F: Fn(&str, &str) -> cmp::Ordering,
+    {
+        let mut order: Vec<usize> = (0..self.rows.len()).collect();
+        sort::sort_indices(&self.rows, &mut order, keys, compare);
+        self.select_rows(&order);
+    }
+
+    /// Remove body rows equal to an earlier row.
+    pub fn dedup_rows(&mut self) {
+        let selection = {
+            let mut seen = HashSet::new();
+            (0..self.rows.len())
+                .filter(|&i| seen.insert(&self.rows[i]))
+                .collect::<Vec<_>>()
+        };
+        self.select_rows(&selection);
+    }
+
+    /// Remove body rows with the same cell in column `idx` as an earlier
+    /// row.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.add_row(["web", "eu"]);
+    /// table.add_row(["db", "eu"]);
+    /// table.add_row(["web", "us"]);
+    /// table.dedup_by_column(0);
+    /// assert_eq!(table.rows().len(), 2);
+    /// assert_eq!(table.rows()[1], ["db", "eu"]);
+    /// ```
+    pub fn dedup_by_column(&mut self, idx: usize) {
+        let selection = {
+            let mut seen = HashSet::new();
+            (0..self.rows.len())
+                .filter(|&i| seen.insert(self.rows[i].get(idx)))
+                .collect::<Vec<_>>()
+        };
+        self.select_rows(&selection);
+    }
+
+    /// Keep only the body rows `selection` in this order.
+    ///
+    /// Tags, cell settings and tree depths move along with their rows,
+    /// dividers stay in front of the first row which was behind them.
+    fn select_rows(&mut self, selection: &[usize]) {
+        let mut rows: Vec<_> = self.rows.drain(..).map(Some).collect();
+        self.tags.resize(rows.len(), vec![]);
+        let mut tags = std::mem::take(&mut self.tags);
+        self.rows = selection.iter().filter_map(|&i| rows[i].take()).collect();
+        self.tags = selection
+            .iter()
+            .map(|&i| std::mem::take(&mut tags[i]))
+            .collect();
+        self.cell_settings.resize(rows.len(), vec![]);
+        let mut settings = std::mem::take(&mut self.cell_settings);
+        self.cell_settings = selection
+            .iter()
+            .map(|&i| std::mem::take(&mut settings[i]))
+            .collect();
+        let depths = std::mem::take(&mut self.depths);
+        self.depths = selection
+            .iter()
+            .map(|&i| depths.get(i).cloned().unwrap_or(0))
+            .collect();
+        for (pos, _) in &mut self.dividers {
+            *pos = selection.iter().filter(|&&i| i < *pos).count();
+        }
+        self.prepared = None;
+    }
+
+    /// Remove all body rows and dividers, keeping the title and
+    /// configuration.
+    pub fn clear_rows(&mut self) {
+        self.rows.clear();
+        self.tags.clear();
+        self.cell_settings.clear();
+        self.depths.clear();
+        self.dividers.clear();
+        self.prepared = None;
+    }
+
+    /// Render into a `String`.
+    ///
+    /// This is the same as `format!("{}", table)` and available without any
+    /// features, e.g. for `wasm32-unknown-unknown`.
+    pub fn render_to_string(&self) -> String {
+        let mut out = String::new();
+        self.render_fmt(&mut out)
+            .expect("writing to a String can't fail");
+        out
+    }
+
+    /// The width and height of the rendered table in terminal columns and
+    /// lines, without rendering it into a `String`.
+    ///
+    /// ```
+    /// use lazytable::{Table, TableConfig};
+    ///
+    /// let mut table = Table::new(TableConfig::default().width(16));
+    /// table.set_title(["name", "note"]);
+    /// table.add_row(["a", "wraps into lines"]);
+    /// assert_eq!(table.measure(), (16, 5));
+    /// ```
+    pub fn measure(&self) -> (usize, usize) {
+        let mut measure = render::Measure::default();
+        self.render_fmt(&mut measure).expect("measuring can't fail");
+        measure.size()
+    }
+
+    /// Render into one `String` per line, without line endings.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.set_title(["a"]);
+    /// table.add_row(["b"]);
+    /// assert_eq!(table.render_lines(), [" a ", "---", " b "]);
+    /// ```
+    pub fn render_lines(&self) -> Vec<String> {
+        self.render_to_string().lines().map(String::from).collect()
+    }
+
+    /// Render into `w` without an intermediate `String`.
+    ///
+    /// ```
+    /// use std::fmt::Write;
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.add_row(["a", "b"]);
+    /// let mut out = String::from("Result:\n");
+    /// table.render_fmt(&mut out).unwrap();
+    /// writeln!(out, "Done.").unwrap();
+    /// assert_eq!(out, "Result:\n a | b \nDone.\n");
+    /// ```
+    pub fn render_fmt<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        TextRenderer.render(self, w)
+    }
+
+    /// A view to render this table with rows filtered, sorted or hidden.
+    pub fn view(&self) -> TableView<'_, 'a> {
+        TableView::new(self)
+    }
+
+    /// Render only body rows tagged with any of `tags`.
+    ///
+    /// Column widths are computed from these rows only.
+    pub fn render_only(&self, tags: &[&str]) -> String {
+        let selection: Vec<usize> = (0..self.rows.len())
+            .filter(|&i| self.row_tags(i).iter().any(|t| tags.contains(t)))
+            .collect();
+        self.render_selection(&selection)
+    }
+
+    /// Render at most `limit` body rows starting at row `offset`.
+    ///
+    /// Column widths are computed from these rows only.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.set_title(["n"]);
+    /// table.extend_rows((0..100).map(|i| vec![i.to_string().into()]));
+    /// assert_eq!(table.render_range(10, 2), " n  \n----\n 10 \n 11 \n");
+    /// ```
+    pub fn render_range(&self, offset: usize, limit: usize) -> String {
+        let end = offset.saturating_add(limit).min(self.rows.len());
+        let selection: Vec<usize> = (offset.min(end)..end).collect();
+        self.render_selection(&selection)
+    }
+
+    /// Render the key column and `count` of the other columns starting at
+    /// `first_visible`, to browse wide tables page by page.
+    ///
+    /// Column widths are computed from these columns only.
+    ///
+    /// ```
+    /// use lazytable::{Table, TableConfig};
+    ///
+    /// let mut table = Table::new(TableConfig::default().key_column(0));
+    /// table.set_title(["name", "jan", "feb", "mar"]);
+    /// table.add_row(["rent", "900", "900", "950"]);
+    /// assert_eq!(
+    ///     table.render_columns_window(1, 2),
+    ///     " name | feb | mar \n------+-----+-----\n rent | 900 | 950 \n"
+    /// );
+    /// ```
+    pub fn render_columns_window(&self, first_visible: usize, count: usize) -> String {
+        let column_count = self.column_count();
+        let key = self.config.key_column.filter(|&idx| idx < column_count);
+        let columns: Vec<usize> = key
+            .into_iter()
+            .chain(
+                (0..column_count)
+                    .filter(|&c| Some(c) != key)
+                    .skip(first_visible)
+                    .take(count),
+            )
+            .collect();
+        let selection = render::Selection {
+            rows: None,
+            columns: Some(&columns),
+        };
+        let mut out = String::new();
+        render::render_text(self, selection, false, &mut out)
+            .expect("writing to a String can't fail");
+        out
+    }
+
+    /// How many body rows `render_range(0, n)` shows in at most `height`
+    /// lines.
+    ///
+    /// Title, rules, dividers and the footer take lines too, as do wrapped
+    /// cells.
+    ///
+    /// ```
+    /// use lazytable::{Table, TableConfig};
+    ///
+    /// let mut table = Table::new(TableConfig::default().width(12));
+    /// table.set_title(["n", "note"]);
+    /// table.add_row(["1", "short"]);
+    /// table.add_row(["2", "a bit longer"]);
+    /// table.add_row(["3", "short"]);
+    /// // The second row wraps into two lines.
+    /// assert_eq!(table.rows_fitting(4), 1);
+    /// assert_eq!(table.rows_fitting(5), 2);
+    /// assert_eq!(table.rows_fitting(2), 0);
+    /// ```
+    pub fn rows_fitting(&self, height: usize) -> usize {
+        // Every row takes at least a line.
+        let most = cmp::min(height, self.rows.len());
+        (1..=most)
+            .take_while(|&n| {
+                let selection: Vec<usize> = (0..n).collect();
+                let mut measure = render::Measure::default();
+                self.fmt_selection(&selection, &mut measure)
+                    .expect("measuring can't fail");
+                measure.size().1 <= height
+            })
+            .last()
+            .unwrap_or(0)
+    }
+
+    fn render_selection(&self, selection: &[usize]) -> String {
+        let mut out = String::new();
+        self.fmt_selection(selection, &mut out)
+            .expect("writing to a String can't fail");
+        out
+    }
+
+    fn fmt_selection(&self, selection: &[usize], f: &mut dyn fmt::Write) -> fmt::Result {
+        let selection = render::Selection {
+            rows: Some(selection),
+            columns: None,
+        };
+        render::render_text(self, selection, false, f)
+    }
+
+    /// Render without any escape sequences, including those in cell content.
+    pub fn render_plain(&self) -> String {
+        let mut out = String::new();
+        PlainRenderer
+            .render(self, &mut out)
+            .expect("writing to a String can't fail");
+        out
+    }
+
+    /// Render as a GitHub flavored Markdown pipe table.
+    ///
+    /// Column alignments become alignment markers of the delimiter row, `|`
+    /// in cells is escaped and line breaks become `<br>`.
+    ///
+    /// ```
+    /// use lazytable::{Alignment, Table};
+    ///
+    /// let mut table = Table::default();
+    /// table.set_title(vec!["op", "n"]);
+    /// table.add_row(vec!["a|b", "1"]);
+    /// table.set_column_alignment(1, Alignment::Right);
+    /// assert_eq!(table.to_markdown(), "| op | n |\n|---|---:|\n| a\\|b | 1 |\n");
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        MarkdownRenderer
+            .render(self, &mut out)
+            .expect("writing to a String can't fail");
+        out
+    }
+
+    /// Render as XML with a `row` element per body row and a `cell` element
+    /// per cell, named by the title.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.set_title(vec!["who"]);
+    /// table.add_row(vec!["<alice>"]);
+    /// assert_eq!(
+    ///     table.to_xml(),
+    ///     "<table><row><cell name=\"who\">&lt;alice&gt;</cell></row></table>"
+    /// );
+    /// ```
+    pub fn to_xml(&self) -> String {
+        let mut out = String::new();
+        XmlRenderer
+            .render(self, &mut out)
+            .expect("writing to a String can't fail");
+        out
+    }
+
+    /// Render as YAML, a sequence of mappings keyed by the title.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.set_title(vec!["who", "what"]);
+    /// table.add_row(vec!["alice", "tea: green"]);
+    /// table.add_row(vec!["bob", "coffee"]);
+    /// assert_eq!(
+    ///     table.to_yaml(),
+    ///     "- who: alice\n  what: \"tea: green\"\n- who: bob\n  what: coffee\n"
+    /// );
+    /// ```
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml(&self) -> String {
+        let mut out = String::new();
+        YamlRenderer
+            .render(self, &mut out)
+            .expect("writing to a String can't fail");
+        out
+    }
+
+    /// Render as SQL statements inserting the body rows into the table
+    /// `table_name`, see `SqlRenderer`.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.set_title(vec!["who", "size"]);
+    /// table.add_row(vec!["a.rs", "120"]);
+    /// assert_eq!(
+    ///     table.to_sql_inserts("files"),
+    ///     "INSERT INTO \"files\" (\"who\", \"size\") VALUES ('a.rs', '120');\n"
+    /// );
+    /// ```
+    pub fn to_sql_inserts(&self, table_name: &str) -> String {
+        let mut out = String::new();
+        SqlRenderer::new(table_name)
+            .render(self, &mut out)
+            .expect("writing to a String can't fail");
+        out
+    }
+
+    /// Write the title and the formatted body rows to `writer` and flush
+    /// it.
+    ///
+    /// Rows are filled with empty cells to the same length and escape
+    /// sequences are stripped from the cells.
+    ///
+    /// ```
+    /// # extern crate csv;
+    /// # extern crate lazytable;
+    /// # fn main() {
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::default();
+    /// table.set_title(vec!["who", "what"]);
+    /// table.add_row(vec!["bob", "coffee, black"]);
+    /// table.add_row(vec!["eve"]);
+    /// let mut writer = csv::Writer::from_writer(vec![]);
+    /// table.write_csv(&mut writer).unwrap();
+    /// let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+    /// assert_eq!(csv, "who,what\nbob,\"coffee, black\"\neve,\n");
+    /// # }
+    /// ```
+    #[cfg(feature = "csv")]
+    pub fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> Result<(), Error> {
+        let columns = self.column_count();
+        let mut write_row = |row: &[Cow<str>]| {
+            writer.write_record((0..columns).map(|idx| {
+                row.get(idx)
+                    .map_or(Cow::Borrowed(""), |c| style::strip_ansi(c))
+                    .into_owned()
+            }))
+        };
+        if let Some(title) = self.title() {
+            write_row(title)?;
+        }
+        for row in self.formatted_rows() {
+            write_row(&row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Render as tab separated values for pasting into spreadsheets, one
+    /// line per row.
+    ///
+    /// Cells are neither wrapped nor truncated regardless of the width and
+    /// content arrangement, see `TsvRenderer`.
+    ///
+    /// ```
+    /// use lazytable::Table;
+    ///
+    /// let mut table = Table::with_width(10);
+    /// table.set_title(vec!["who", "what"]);
+    /// table.add_row(vec!["alice", "a rather long\tcell"]);
+    /// assert_eq!(table.to_plain_tsv(), "who\twhat\nalice\ta rather long cell\n");
+    /// ```
+    pub fn to_plain_tsv(&self) -> String {
+        let mut out = String::new();
+        TsvRenderer
+            .render(self, &mut out)
+            .expect("writing to a String can't fail");
+        out
+    }
+
+    /// The title row.
+    pub fn title(&self) -> Option<&[Cow<'a, str>]> {
+        self.title.as_ref().map(|t| &t[..])
+    }
+
+    /// The footer row.
+    pub fn footer(&self) -> Option<&[Cow<'a, str>]> {
+        self.footer.as_ref().map(|f| &f[..])
+    }
+
+    /// The body rows.
+    pub fn rows(&self) -> &[Row<'a>] {
+        &self.rows
+    }
+
+    /// The content widths of all columns fitted into the configured width.
+    pub fn column_widths(&self) -> Vec<usize> {
+        let columns = self.display_columns(None);
+        let title = self.display_title(&columns);
+        let footer = self.display_footer(&columns);
+        self.dimensions(
+            title.as_deref(),
+            footer.as_deref(),
+            &self.display_rows(&self.tree_guides()),
+            &columns,
+        )
+    }
+
+    /// The style of the last matching `set_tag_style` for body row `i`.
+    fn tag_style(&self, i: usize) -> Option<Style> {
+        let tags = self.row_tags(i);
+        self.tag_styles
+            .iter()
+            .rev()
+            .find(|(tag, _)| tags.contains(tag))
+            .map(|(_, style)| *style)
+    }
+
+    /// Styles of the cells of body row `i` from row style, tag styles,
+    /// column formats, heatmaps, rules and the styles of cells added with
+    /// `add_cells`.
+    pub fn cell_styles(&self, i: usize) -> Vec<Option<Style>> {
+        self.styles(i, &self.heat_ranges())
+    }
+
+    /// Like `cell_styles` with the `heat_ranges` of the columns.
+    fn styles(&self, i: usize, heat_ranges: &[Option<(f64, f64)>]) -> Vec<Option<Style>> {
+        let row = &self.rows[i];
+        let style = self
+            .row_style
+            .as_ref()
+            .and_then(|s| s(i, row))
+            .or_else(|| self.tag_style(i));
+        row.iter()
+            .enumerate()
+            .map(|(col, cell)| {
+                self.cell_settings(i, col)
+                    .and_then(|s| s.style)
+                    .or_else(|| {
+                        self.rules
+                            .iter()
+                            .rev()
+                            .find_map(|rule| rule.eval(col, cell))
+                            .or_else(|| {
+                                let heatmap = self.columns.get(col)?.heatmap?;
+                                heatmap.style(cell, heat_ranges.get(col).cloned()??)
+                            })
+                            .or_else(|| self.columns.get(col)?.format?.style(cell))
+                            .or(style)
+                    })
+            })
+            .collect()
+    }
+
+    /// The value ranges of columns with a heatmap.
+    fn heat_ranges(&self) -> Vec<Option<(f64, f64)>> {
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(col, column)| {
+                let cells = self.rows.iter().filter_map(|row| row.get(col));
+                column.heatmap?.range_of(cells.map(|c| c.as_ref()))
+            })
+            .collect()
+    }
+
+    /// Body rows with column formats applied.
+    pub fn formatted_rows(&self) -> Vec<Vec<Cow<'_, str>>> {
+        (0..self.rows.len())
+            .map(|i| self.formatted_row(i))
+            .collect()
+    }
+
+    /// Body row `i` with column formats applied.
+    fn formatted_row(&self, i: usize) -> Vec<Cow<'_, str>> {
+        self.rows[i]
+            .iter()
+            .enumerate()
+            .map(|(col, cell)| {
+                if self.is_none(i, col) {
+                    return Cow::Borrowed(self.none_placeholder);
+                }
+                let column = self.columns.get(col);
+                let cell = match column.and_then(|c| c.format) {
+                    Some(format) => format.apply(cell),
+                    None => Cow::Borrowed(cell.as_ref()),
+                };
+                let cell = match column.and_then(|c| c.formatter.as_ref()) {
+                    Some(formatter) => Cow::Owned(formatter(&cell)),
+                    None => cell,
+                };
+                self.sanitize(cell)
+            })
+            .collect()
+    }
+
+    /// The displayed columns, `None` being the row number column.
+    ///
+    /// `columns` are the data columns to display, all if it's `None`.
+    fn display_columns(&self, columns: Option<&[usize]>) -> Vec<Option<usize>> {
+        let numbers = if self.config.show_row_numbers {
+            Some(None)
+        } else {
+            None
+        };
+        let data: Vec<_> = match columns {
+            Some(columns) => columns.iter().cloned().map(Some).collect(),
+            None => (0..self.column_count()).map(Some).collect(),
+        };
+        let mut columns: Vec<_> = numbers.into_iter().chain(data).collect();
+        if self.config.right_to_left {
+            columns.reverse();
+        }
+        columns
+    }
+
+    /// The number of cells of the longest row.
+    fn column_count(&self) -> usize {
+        self.title
+            .iter()
+            .chain(&self.footer)
+            .chain(&self.rows)
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Body row `i` as it is displayed in `columns` with the tree `guides`
+    /// of all rows.
+    fn display_row(
+        &self,
+        i: usize,
+        columns: &[Option<usize>],
+        guides: &[String],
+    ) -> Vec<Cow<'_, str>> {
+        let mut row = self.formatted_row(i);
+        if let Some(guide) = guides.get(i).filter(|g| !g.is_empty()) {
+            if let Some(first) = row.first_mut() {
+                *first = Cow::Owned(format!("{}{}", guide, first.trim_start()));
+            }
+        }
+        if self.config.right_to_left {
+            // Short rows end on the left.
+            row.resize(self.column_count(), Cow::Borrowed(""));
+        }
+        project(row, || (i + 1).to_string(), columns)
+    }
+
+    /// All body rows as they are displayed with their tree `guides`.
+    fn display_rows(&self, guides: &[String]) -> Vec<Vec<Cow<'_, str>>> {
+        let columns = self.display_columns(None);
+        let mut rows: Vec<_> = (0..self.rows.len())
+            .map(|i| self.display_row(i, &columns, guides))
+            .collect();
+        self.suppress_repeats(&mut rows, &columns);
+        rows
+    }
+
+    /// Replace cells of displayed `rows` equal to the cell above with the
+    /// repeat marker of their column.
+    fn suppress_repeats<'c>(&self, rows: &mut [Vec<Cow<'c, str>>], columns: &[Option<usize>])
+    where
+        'a: 'c,
+    {
+        for (pos, column) in columns.iter().enumerate() {
+            let marker = match column.and_then(|c| self.columns.get(c)?.repeat_marker) {
+                Some(marker) => marker,
+                None => continue,
+            };
+            let mut previous: Option<String> = None;
+            for cell in rows.iter_mut().filter_map(|row| row.get_mut(pos)) {
+                if !cell.is_empty() && previous.as_deref() == Some(&**cell) {
+                    *cell = Cow::Borrowed(marker);
+                } else {
+                    previous = Some(cell.to_string());
+                }
+            }
+        }
+    }
+
+    /// The title row as it is displayed in `columns`.
+    fn display_title(&self, columns: &[Option<usize>]) -> Option<Vec<Cow<'_, str>>> {
+        self.title
+            .as_ref()
+            .map(|t| self.display_unformatted(t, "#", columns))
+    }
+
+    /// The footer row as it is displayed in `columns`.
+    fn display_footer(&self, columns: &[Option<usize>]) -> Option<Vec<Cow<'_, str>>> {
+        self.footer
+            .as_ref()
+            .map(|f| self.display_unformatted(f, "", columns))
+    }
+
+    /// A row without column formats as it is displayed in `columns`,
+    /// `number` taking the place of the row number.
+    fn display_unformatted<'c>(
+        &self,
+        row: &'c [Cow<'a, str>],
+        number: &str,
+        columns: &[Option<usize>],
+    ) -> Vec<Cow<'c, str>> {
+        let mut row: Vec<_> = row
+            .iter()
+            .map(|c| self.sanitize(Cow::Borrowed(c.as_ref())))
+            .collect();
+        if self.config.right_to_left {
+            row.resize(self.column_count(), Cow::Borrowed(""));
+        }
+        project(row, || number.to_owned(), columns)
+    }
+
+    /// The alignment of a displayed column.
+    fn display_alignment(&self, column: Option<usize>) -> Alignment {
+        match column {
+            Some(idx) => self.column_alignment(idx),
+            None => Alignment::Right,
+        }
+    }
+
+    /// The alignment of the cell of body row `i` in a displayed column.
+    fn display_cell_alignment(&self, i: usize, column: Option<usize>) -> Alignment {
+        column
+            .and_then(|c| self.cell_settings(i, c)?.alignment)
+            .unwrap_or_else(|| self.display_alignment(column))
+    }
+
+    /// The ellipsis marker of a displayed column.
+    fn display_marker(&self, column: Option<usize>) -> &'a str {
+        column
+            .and_then(|c| self.columns.get(c)?.ellipsis_marker)
+            .unwrap_or(self.config.ellipsis_marker)
+    }
+
+    fn sanitize<'c>(&self, cell: Cow<'c, str>) -> Cow<'c, str> {
+        let cell = match self.config.sanitized {
+            Some(max_len) => match cell {
+                Cow::Borrowed(cell) => sanitize::untrusted(cell, max_len),
+                Cow::Owned(cell) => match sanitize::untrusted(&cell, max_len) {
+                    Cow::Borrowed(_) => Cow::Owned(cell),
+                    Cow::Owned(sanitized) => Cow::Owned(sanitized),
+                },
+            },
+            None => cell,
+        };
+        match cell {
+            Cow::Borrowed(cell) => self.config.control_chars.apply(cell),
+            Cow::Owned(cell) => match self.config.control_chars.apply(&cell) {
+                Cow::Borrowed(_) => Cow::Owned(cell),
+                Cow::Owned(sanitized) => Cow::Owned(sanitized),
+            },
+        }
+    }
+
+    fn dimensions<S: AsRef<str>>(
+        &self,
+        title: Option<&[S]>,
+        footer: Option<&[S]>,
+        rows: &[Vec<S>],
+        columns: &[Option<usize>],
+    ) -> Vec<usize> {
+        let dimensions = rows
+            .iter()
+            .map(|x| &x[..])
+            .chain(title)
+            .chain(footer)
+            .map(|x| x.iter().map(|s| cell_width(s.as_ref())).collect::<Vec<_>>())
+            .fold(vec::Vec::<usize>::new(), |l, r| max_merge(&l, &r));
+        let mut dimensions = dimensions;
+        for (dim, column) in dimensions.iter_mut().zip(columns) {
+            if let Some(width) = column.and_then(|c| self.columns.get(c)?.width) {
+                *dim = width;
+            }
+        }
+        if let Some(ref frozen) = self.frozen {
+            for (dim, column) in dimensions.iter_mut().zip(columns) {
+                if let Some(&(_, width)) = frozen.iter().find(|(c, _)| c == column) {
+                    *dim = width;
+                }
+            }
+            return dimensions;
+        }
+        let mut width = self.width().saturating_sub(self.config.frame_width());
+        if !self.config.pad_last_column {
+            // Distributed as if the last column was padded.
+            width += self.config.padding;
+        }
+        let padding = self.config.padding;
+        let separator = self.config.separator_width();
+        match self.config.arrangement {
+            ContentArrangement::Disabled => dimensions,
+            ContentArrangement::Dynamic => distribute(&dimensions, width, padding, separator),
+            ContentArrangement::DynamicFullWidth => stretch(
+                &distribute(&dimensions, width, padding, separator),
+                width,
+                padding,
+                separator,
+            ),
+            ContentArrangement::MinimizeHeight => {
+                let cols = dimensions.len();
+                // Columns and all but one separator.
+                let budget = (width + separator).saturating_sub(cols * (2 * padding + separator));
+                let all: Vec<&[S]> = rows
+                    .iter()
+                    .map(|x| &x[..])
+                    .chain(title)
+                    .chain(footer)
+                    .collect();
+                let fair = distribute(&dimensions, width, padding, separator);
+                balance::minimize_height(&all, &dimensions, fair, budget, self.config.wrapping)
+            }
+        }
+    }
+}
+
+impl<'a> fmt::Display for Table<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        TextRenderer.render(self, f)
+    }
+}
+
+/// A table without title and the default configuration.
+///
+/// ```
+/// use lazytable::Table;
+///
+/// let rows = vec![vec!["a".to_owned(), "1".to_owned()]];
+/// let table: Table = rows.into();
+/// assert_eq!(table.to_string(), " a | 1 \n");
+/// ```
+impl<'a> From<Vec<Vec<String>>> for Table<'a> {
+    fn from(rows: Vec<Vec<String>>) -> Table<'a> {
+        let mut table = Table::default();
+        table.extend_rows(
+            rows.into_iter()
+                .map(|r| r.into_iter().map(Cow::Owned).collect()),
+        );
+        table
+    }
+}
+
+/// A table without title and the default configuration.
+///
+/// ```
+/// use lazytable::Table;
+///
+/// let table = Table::from(&[&["a", "1"][..], &["b", "2"]][..]);
+/// assert_eq!(table.to_string(), " a | 1 \n b | 2 \n");
+/// ```
+impl<'a> From<&[&[&'a str]]> for Table<'a> {
+    fn from(rows: &[&[&'a str]]) -> Table<'a> {
+        let mut table = Table::default();
+        table.extend_rows(
+            rows.iter()
+                .map(|r| r.iter().map(|&c| Cow::Borrowed(c)).collect()),
+        );
+        table
+    }
+}
+
+/// The width of the terminal attached to stdout.
+#[cfg(feature = "terminal_size")]
+pub fn terminal_width() -> Option<usize> {
+    terminal_size::terminal_size().map(|(terminal_size::Width(w), _)| usize::from(w))
+}
+
+/// The cells of `row` in the displayed `columns`, `number` is called for the
+/// row number column.
+///
+/// Stops at the first missing cell like a short row would.
+fn project<'c, F>(row: Vec<Cow<'c, str>>, number: F, columns: &[Option<usize>]) -> Vec<Cow<'c, str>>
+where
+    F: FnOnce() -> String,
+{
+    let mut cells: Vec<_> = row.into_iter().map(Some).collect();
+    let mut number = Some(number);
+    columns
+        .iter()
+        .map_while(|column| match *column {
+            Some(idx) => cells.get_mut(idx)?.take(),
+            None => number.take().map(|n| Cow::Owned(n())),
+        })
+        .collect()
+}
+
+/// The width of `s` in terminal columns, ignoring escape sequences.
+fn display_width(s: &str) -> usize {
+    UnicodeWidthStr::width(&*style::strip_ansi(s))
+}
+
+/// The width of the widest line of `cell`.
+fn cell_width(cell: &str) -> usize {
+    cell.split('\n').map(display_width).max().unwrap_or(0)
+}
+
+/// Wrap all cells of `row` to their column's width.
+fn wrap<'c, S: AsRef<str>>(
+    row: &'c [S],
+    dimensions: &[usize],
+    config: &TableConfig,
+) -> Vec<Vec<Line<'c>>> {
+    dimensions
+        .iter()
+        .zip(row.iter())
+        .map(|(dim, cell)| {
+            let cell = cell.as_ref();
+            let lines = config.split(cell, *dim);
+            lines.into_iter().map(|r| Line::of(cell, r)).collect()
+        })
+        .collect()
+}
+
+/// `range` moved `offset` bytes further.
+fn shift(range: Range<usize>, offset: usize) -> Range<usize> {
+    range.start + offset..range.end + offset
+}
+
+/// The byte ranges of `cell` wrapped into lines of at most `w` columns,
+/// breaking at spaces if possible. Wide characters are never split, a character wider than `w`
+/// gets a line of its own.
+fn split(cell: &str, w: usize) -> Vec<Range<usize>> {
+    let mut lines = vec![];
+    let mut rest = cell;
+    while !rest.is_empty() {
+        let offset = cell.len() - rest.len();
+        let mut till = 0;
+        let mut width = 0;
+        let mut space = None;
+        let mut text = false;
+        for (i, c) in rest.char_indices() {
+            width += c.width().unwrap_or(0);
+            if width > w {
+                break;
+            }
+            // Don't break in the indentation of the first line.
+            if c == ' ' && (text || !lines.is_empty()) {
+                space = Some(i + 1);
+            }
+            text |= c != ' ';
+            till = i + c.len_utf8();
+        }
+        if till == 0 {
+            till = rest.chars().next().map_or(1, char::len_utf8);
+        }
+        let i = if till < rest.len() {
+            space.unwrap_or(till)
+        } else {
+            till
+        };
+        let line = &rest[..i];
+        let start = offset + line.len() - line.trim_start().len();
+        lines.push(start..start + line.trim().len());
+        rest = &rest[i..];
+    }
+    lines
+}
+
+/// The width of a column of `col_width` with `width` left for `cols`
+/// columns, at least one unless the column is empty.
+fn flying(col_width: usize, cols: usize, width: usize, padding: usize, separator: usize) -> usize {
+    let space = cols * (2 * padding + separator) - separator;
+    let fair = width.saturating_sub(space) / cols;
+    cmp::min(col_width, cmp::max(fair, 1))
+}
+
+fn max_merge(left: &[usize], right: &[usize]) -> Vec<usize> {
+    let mut merged = left.iter()
+        .zip(right.iter())
+        .map(|(l, r)| *cmp::max(l, r))
+        .collect::<Vec<_>>();
+    let both = merged.len();
+    merged.append(&mut left.iter().skip(both).cloned().collect::<Vec<_>>());
+    merged.append(&mut right.iter().skip(both).cloned().collect::<Vec<_>>());
+    merged
+}
+
+/// Shrink `dimensions` to fit `width`, narrow columns first.
+///
+/// Columns are shrunk to no less than one column. If even that is too wide
+/// the last columns are dropped, keeping at least one. Separators are
+/// `separator` columns wide.
+fn distribute(dimensions: &[usize], width: usize, padding: usize, separator: usize) -> Vec<usize> {
+    // Columns of width one with their padding and separators.
+    let fitting = cmp::max((width + separator) / (2 * padding + 1 + separator), 1);
+    let dimensions = &dimensions[..cmp::min(dimensions.len(), fitting)];
+    let mut indexed = dimensions.iter().cloned().enumerate().collect::<Vec<_>>();
+    indexed.sort_by_key(|a| a.1);
+    let mut width = width;
+    let mut cols = dimensions.len();
+    let mut distributed = indexed
+        .iter()
+        .map(|&(i, x)| {
+            let size = flying(x, cols, width, padding, separator);
+            cols -= 1;
+            width = width.saturating_sub(size + 2 * padding + separator);
+            (i, size)
+        })
+        .collect::<Vec<_>>();
+    distributed.sort_by_key(|a| a.0);
+    distributed.iter().map(|&(_, x)| x).collect()
+}
+
+/// Widen `dimensions` evenly until they fill `width`.
+fn stretch(dimensions: &[usize], width: usize, padding: usize, separator: usize) -> Vec<usize> {
+    let cols = dimensions.len();
+    if cols == 0 {
+        return vec![];
+    }
+    let used = dimensions.iter().sum::<usize>() + cols * 2 * padding + (cols - 1) * separator;
+    let extra = width.saturating_sub(used);
+    dimensions
+        .iter()
+        .enumerate()
+        .map(|(i, dim)| dim + extra / cols + if i < extra % cols { 1 } else { 0 })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    macro_rules! ownv {
+        ($($s:expr),*) => { vec!($(Cow::from($s)), *) }
+    }
+
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+    /// The wrapped `lines` of `cell`.
+    fn lines(cell: &str, lines: Vec<Range<usize>>) -> Vec<&str> {
+        lines.into_iter().map(|r| &cell[r]).collect()
+    }
+
+    fn split(cell: &str, w: usize) -> Vec<&str> {
+        lines(cell, super::split(cell, w))
+    }
+
+    #[test]
+    fn it_works() {
+        let mut table = Table::default();
+        table.set_title(ownv!["who", "what"]);
+        table.extend_rows(vec![ownv!["a", "b"], ownv!["c", "d"]]);
+        table.add_row(ownv!["foobar", "foobar2000"]);
+        let columns = table.display_columns(None);
+        assert_eq!(
+            table.dimensions(table.title(), None, &table.rows, &columns),
+            vec![6, 10]
+        );
+        let out = format!("{}", table);
+        let should = "\
+# who    | what       #
+#--------+------------#
+# a      | b          #
+# c      | d          #
+# foobar | foobar2000 #
+"
+            .replace("#", "");
+        assert_eq!(out, should);
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn send_sync<T: Send + Sync>() {}
+        send_sync::<Table>();
+        send_sync::<typed::TypedTable<u8>>();
+    }
+
+    #[test]
+    fn test_row_macro() {
+        let owned = String::from("b");
+        let row = row!["a", owned];
+        assert!(matches!(row[0], Cow::Borrowed("a")));
+        assert!(matches!(row[1], Cow::Owned(ref s) if s == "b"));
+        assert_eq!(row![Some(1.5), None::<f64>], ["1.5", ""]);
+    }
+
+    #[test]
+    fn test_add_row() {
+        let owned = [String::from("c"), String::from("d")];
+        let mut table = Table::default();
+        table.add_row(["a", "b"]);
+        table.add_row(&owned[..]);
+        table.add_row(vec![Cow::from("e"), Cow::from(String::from("f"))]);
+        table.add_row("g h".split(' ').map(str::to_uppercase));
+        assert_eq!(table.rows()[1], ownv!["c", "d"]);
+        assert_eq!(table.rows()[2], ownv!["e", "f"]);
+        assert_eq!(table.rows()[3], ownv!["G", "H"]);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_add_rows() {
+        let mut table = Table::default();
+        table.extend_rows((0..2).map(|i| row![i.to_string()]));
+        let mut rows = vec![ownv!["2"]];
+        table.add_rows(&mut rows);
+        assert!(rows.is_empty());
+        assert_eq!(table.rows(), &[ownv!["0"], ownv!["1"], ownv!["2"]][..]);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut table = Table::with_width(20);
+        table.set_title(["a", "b"]);
+        table.add_row(["c", "d"]);
+        table.set_title(vec![String::from("x")]);
+        assert_eq!(table.title(), Some(&ownv!["x"][..]));
+        table.clear_title();
+        table.clear_rows();
+        assert_eq!(table.title(), None);
+        assert_eq!(table.render_to_string(), "");
+        table.add_row(["e"]);
+        assert_eq!(table.render_to_string(), " e \n");
+    }
+
+    #[test]
+    fn test_center_title() {
+        let mut table = Table::new(TableConfig::default().center_title(true));
+        table.set_column_format(1, Format::Currency("USD"));
+        table.set_title(["name", "price"]);
+        table.add_row(["abcdefgh", "1000"]);
+        let should = "\
+#   name   |   price   #
+#----------+-----------#
+# abcdefgh | $1,000.00 #
+"
+        .replace("#", "");
+        assert_eq!(table.render_to_string(), should);
+    }
+
+    #[test]
+    fn test_title_rule() {
+        let config = TableConfig::default()
+            .frame(true)
+            .title_rule("=")
+            .title_rule_style(Style::blue());
+        let mut table = Table::new(config);
+        table.set_title(["a", "b"]);
+        table.add_row(["c", "d"]);
+        let should = "\
++---+---+
+| a | b |
++===+===+
+| c | d |
++---+---+
+";
+        assert_eq!(table.render_plain(), should);
+        table.force_color(true);
+        let out = table.render_to_string();
+        assert_eq!(out.lines().nth(2), Some("\x1b[34m+===+===+\x1b[0m"));
+        table.config.frame = false;
+        table.config.title_rule_chars = Some(RuleChars {
+            cross: "#",
+            ..RuleChars::ascii_double()
+        });
+        assert_eq!(table.render_plain(), " a | b \n===#===\n c | d \n");
+    }
+
+    #[test]
+    fn test_outer_rules() {
+        let config = TableConfig::default()
+            .border(BorderChars::unicode())
+            .outer_rules(true);
+        let mut table = Table::new(config);
+        table.add_row(["a", "b"]);
+        assert_eq!(table.render_to_string(), "───┬───\n a │ b \n───┴───\n");
+        table.config.layout = Layout::Record;
+        let should = "─[ RECORD 1 ]─\n  │ a         \n  │ b         \n──────────────\n";
+        assert_eq!(table.render_to_string(), should);
+    }
+
+    #[test]
+    fn test_footer() {
+        let config = TableConfig::default()
+            .frame(true)
+            .show_row_numbers(true)
+            .footer_rule("=");
+        let mut table = Table::new(config);
+        table.add_row(["a", "1"]);
+        table.add_row(["b", "2"]);
+        table.set_footer(["sum", "3"]);
+        let should = "\
++---+-----+---+
+| 1 | a   | 1 |
+| 2 | b   | 2 |
++===+=====+===+
+|   | sum | 3 |
++---+-----+---+
+";
+        assert_eq!(table.render_to_string(), should);
+        assert_eq!(table.render_range(1, 1).lines().count(), 5);
+        table.config.separate_footer = false;
+        table.config.frame = false;
+        table.reorder_columns(&[1]);
+        assert_eq!(table.footer(), Some(&ownv!["3", "sum"][..]));
+        let should = " 1 | 1 | a   \n 2 | 2 | b   \n   | 3 | sum \n";
+        assert_eq!(table.render_to_string(), should);
+        table.clear_footer();
+        assert_eq!(table.render_to_string().lines().count(), 2);
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut table = Table::new(TableConfig::default().empty(Empty::Nothing));
+        table.set_title(["a"]);
+        assert_eq!(table.to_string(), "");
+        let config = TableConfig::default()
+            .frame(true)
+            .empty(Empty::Placeholder("none"));
+        let mut table = Table::new(config);
+        assert_eq!(table.to_string(), "+------+\n| none |\n+------+\n");
+        table.add_row(["a"]);
+        assert_eq!(table.to_string(), "+---+\n| a |\n+---+\n");
+        table.clear_rows();
+        table.config.layout = Layout::Record;
+        assert_eq!(table.to_string(), "+------+\n| none |\n+------+\n");
+        table.config.empty = Empty::Nothing;
+        assert_eq!(table.to_string(), "");
+    }
+
+    #[test]
+    fn test_spacer() {
+        let mut table = Table::default();
+        table.set_title(["a", "b"]);
+        table.add_spacer();
+        table.add_row(["c", "d"]);
+        table.add_spacer();
+        table.add_spacer();
+        table.add_row(["e", "f"]);
+        table.add_spacer();
+        let should = "\
+# a | b #
+#---+---#
+#       #
+# c | d #
+#       #
+#       #
+# e | f #
+#       #
+"
+        .replace("#", "");
+        assert_eq!(table.render_to_string(), should);
+        table.config = TableConfig::default().frame(true);
+        table.clear_rows();
+        table.add_row(["c", "d"]);
+        table.add_spacer();
+        table.add_row(["e", "f"]);
+        let should = "\
++---+---+
+| a | b |
++---+---+
+| c | d |
+|       |
+| e | f |
++---+---+
+";
+        assert_eq!(table.render_to_string(), should);
+    }
+
+    #[test]
+    fn test_section() {
+        let mut table = Table::new(TableConfig::default().width(13).frame(true));
+        table.set_title(["a", "b"]);
+        table.add_section("first sec");
+        table.add_row(["c", "d"]);
+        table.add_section("two");
+        table.set_section_style(Style::new().bold());
+        let should = "\
++---+---+
+| a | b |
++---+---+
+| first |
+| sec   |
+| c | d |
+| two   |
++---+---+
+";
+        assert_eq!(table.render_plain(), should);
+        table.force_color(true);
+        assert!(table.render_to_string().contains("| \x1b[1mtwo\x1b[0m   |"));
+    }
+
+    #[test]
+    fn test_tags() {
+        let mut table = Table::default();
+        table.set_title(["host", "state"]);
+        table.add_section("eu");
+        table.add_row(["a", "up"]);
+        table.add_row_tagged(["bb", "down"], &["error"]);
+        table.add_section("us");
+        table.add_row(["c", "up"]);
+        table.add_section("asia");
+        table.add_row_tagged(["d", "down"], &["error", "asia"]);
+        assert_eq!(table.row_tags(0), &[] as &[&str]);
+        assert_eq!(table.row_tags(3), &["error", "asia"]);
+        let should = "\
+# host | state #
+#------+-------#
+# eu           #
+# bb   | down  #
+# asia         #
+# d    | down  #
+"
+        .replace("#", "");
+        assert_eq!(table.render_only(&["error"]), should);
+        table.set_tag_style("error", Style::red());
+        table.set_tag_style("asia", Style::blue());
+        table.force_color(true);
+        assert_eq!(table.cell_styles(0), vec![None, None]);
+        assert_eq!(table.cell_styles(1), vec![Some(Style::red()); 2]);
+        assert_eq!(table.cell_styles(3), vec![Some(Style::blue()); 2]);
+    }
+
+    #[test]
+    fn test_from_columns() {
+        let table =
+            Table::from_columns(vec![("a", vec!["1", "2"]), ("b", vec!["3", "4"])]).unwrap();
+        assert_eq!(table.title().unwrap(), ["a", "b"]);
+        assert_eq!(table.rows(), [vec!["1", "3"], vec!["2", "4"]]);
+        let err = Table::from_columns(vec![("a", vec!["1"]), ("b", vec![]), ("c", vec!["2"])]);
+        match err {
+            Err(Error::ColumnLength { idx, len, expected }) => {
+                assert_eq!((idx, len, expected), (1, 0, 1))
+            }
+            _ => panic!("columns of different lengths"),
+        }
+        let empty = Table::from_columns(Vec::<(&str, Vec<&str>)>::new()).unwrap();
+        assert!(empty.rows().is_empty());
+    }
+
+    #[test]
+    fn test_add_cells() {
+        let mut table = Table::default();
+        table.add_row(["b", "bb"]);
+        table.add_cells(
+            RowBuilder::new()
+                .push_styled("a", Style::red())
+                .push_aligned(1, Alignment::Right),
+        );
+        table.sort_by_columns(&[(0, Order::Ascending)]);
+        assert_eq!(table.cell_styles(0), vec![Some(Style::red()), None]);
+        assert_eq!(table.cell_styles(1), vec![None, None]);
+        assert_eq!(table.to_string(), " a |  1 \n b | bb \n");
+        table.reorder_columns(&[1]);
+        assert_eq!(table.cell_styles(0), vec![None, Some(Style::red())]);
+        assert_eq!(table.to_string(), "  1 | a \n bb | b \n");
+
+        let mut table = Table::with_width(9);
+        table.add_cells([
+            Cell::new("x"),
+            Cell::sparkline(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]),
+        ]);
+        table.prepare();
+        assert_eq!(table.to_string(), " x | ▂▅▇ \n");
+    }
+
+    #[test]
+    fn test_highlight() {
+        let mut table = Table::with_width(14);
+        table.set_title(["abc", "x"]);
+        table.add_row(["foo barbaz", "bar"]);
+        table.highlight("ba", Style::red());
+        table.highlight("o b", Style::blue());
+        table.set_row_style_fn(|_, _| Some(Style::green()));
+        let should = "\
+# abc    | x   #
+#--------+-----#
+# foo    | bar #
+# barbaz |     #
+"
+        .replace("#", "");
+        assert_eq!(table.render_plain(), should);
+        table.force_color(true);
+        let out = table.render_to_string();
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines[0], " abc    | x   ");
+        let (red, blue, green, reset) = ("\x1b[31m", "\x1b[34m", "\x1b[32m", "\x1b[0m");
+        assert_eq!(
+            lines[2],
+            format!(
+                " {g}fo{r}{b}o{r}    | {R}ba{r}{g}r{r} ",
+                g = green,
+                b = blue,
+                R = red,
+                r = reset
+            )
+        );
+        assert_eq!(
+            lines[3],
+            format!(
+                " {b}b{r}{g}ar{r}{R}ba{r}{g}z{r} |     ",
+                g = green,
+                b = blue,
+                R = red,
+                r = reset
+            )
+        );
+
+        // Lines of column wrappers aren't part of the cell.
+        let mut table = Table::default();
+        table.add_row(["x\nbar", "bar"]);
+        table.set_column_wrapper(1, |cell, _| vec![cell.to_uppercase()]);
+        table.highlight("ba", Style::red());
+        table.force_color(true);
+        let should = format!(" x   | BAR \n {R}ba{r}r |     \n", R = red, r = reset);
+        assert_eq!(table.render_to_string(), should);
+    }
+
+    #[test]
+    fn test_row_numbers() {
+        let mut table = Table::new(TableConfig::default().show_row_numbers(true));
+        table.set_title(["a"]);
+        for i in 0..10 {
+            table.add_row_tagged([i.to_string()], if i % 9 == 0 { &["x"] } else { &[] });
+        }
+        table.set_column_format(0, Format::Currency("EUR"));
+        let should = "  # |     a \n----+-------\n  1 | €0.00 \n 10 | €9.00 \n";
+        assert_eq!(table.render_only(&["x"]), should);
+        assert_eq!(table.column_widths(), vec![2, 5]);
+        table.prepare();
+        assert!(table.render_to_string().ends_with(" 10 | €9.00 \n"));
+    }
+
+    #[test]
+    fn test_reorder_columns() {
+        let mut table = Table::default();
+        table.set_title(["name", "price", "note"]);
+        table.add_row(["a", "1", "x"]);
+        table.add_row(["b", "2"]);
+        table.add_row(["c"]);
+        table.set_column_format(1, Format::Currency("USD"));
+        table.add_rule(Rule::column(1).gt(1.0).style(Style::red()));
+        table.reorder_columns_by_name(&["price"]);
+        assert_eq!(table.title(), Some(&ownv!["price", "name", "note"][..]));
+        assert_eq!(
+            table.rows(),
+            &[ownv!["1", "a", "x"], ownv!["2", "b"], ownv!["", "c"],][..]
+        );
+        assert_eq!(table.column_alignment(0), Alignment::Right);
+        assert_eq!(table.column_alignment(1), Alignment::Left);
+        table.force_color(true);
+        assert_eq!(table.cell_styles(1), vec![Some(Style::red()), None]);
+        table.reorder_columns(&[2, 1, 0]);
+        assert_eq!(table.title(), Some(&ownv!["note", "name", "price"][..]));
+        assert_eq!(table.rows()[2], ownv!["", "c", ""]);
+        assert_eq!(table.column_alignment(2), Alignment::Right);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_reorder_columns_twice() {
+        let mut table = Table::default();
+        table.add_row(["a", "b"]);
+        table.reorder_columns(&[1, 1]);
+    }
+
+    #[test]
+    fn test_column_by_name() {
+        let mut table = Table::default();
+        table.set_title(["name", "price", "note"]);
+        table.add_row(["a", "1", "some note"]);
+        assert_eq!(table.column_index("note"), Some(2));
+        assert_eq!(table.column_index("nope"), None);
+        table.set_column_format_by_name("price", Format::Currency("USD"));
+        table.set_column_alignment_by_name("name", Alignment::Right);
+        table.set_column_width_by_name("note", 5);
+        table.set_column_alignment(1, Alignment::Center);
+        assert_eq!(table.column_alignment(1), Alignment::Center);
+        let should = "\
+# name | price | note  #
+#------+-------+-------#
+#    a | $1.00 | some  #
+#      |       | note  #
+"
+        .replace("#", "");
+        assert_eq!(table.render_to_string(), should);
+    }
+
+    #[test]
+    #[should_panic(expected = "no column named")]
+    fn test_column_by_unknown_name() {
+        let mut table = Table::default();
+        table.set_title(["a"]);
+        table.set_column_width_by_name("b", 1);
+    }
+
+    #[test]
+    fn test_map_column() {
+        let mut table = Table::default();
+        table.set_title(["state"]);
+        table.add_row(["up"]);
+        table.add_row(Vec::<String>::new());
+        table.map_column(0, |c| format!("<{}>", c));
+        assert_eq!(table.title(), Some(&ownv!["state"][..]));
+        assert_eq!(table.rows(), &[ownv!["<up>"], vec![]][..]);
+    }
+
+    #[test]
+    fn test_computed_column() {
+        let mut table = Table::default();
+        table.set_title(["a", "b"]);
+        table.add_row(["1", "2"]);
+        table.add_row(["3"]);
+        table.add_computed_column("len", |row| row.len().to_string());
+        assert_eq!(table.title(), Some(&ownv!["a", "b", "len"][..]));
+        assert_eq!(
+            table.rows(),
+            &[ownv!["1", "2", "2"], ownv!["3", "", "1"]][..]
+        );
+        let mut table = Table::default();
+        table.add_computed_column("x", |_| "y");
+        assert_eq!(table.title(), None);
+    }
+
+    #[test]
+    fn test_sort_by_columns() {
+        let mut table = Table::default();
+        table.add_row_tagged(["b", "2"], &["x"]);
+        table.add_row(["a", "2"]);
+        table.add_spacer();
+        table.add_row(["c", "1"]);
+        table.add_row(["a"]);
+        table.sort_by_columns(&[(1, Order::Ascending), (0, Order::Descending)]);
+        assert_eq!(
+            table.rows(),
+            &[
+                ownv!["a"],
+                ownv!["c", "1"],
+                ownv!["b", "2"],
+                ownv!["a", "2"]
+            ][..]
+        );
+        assert_eq!(table.row_tags(2), &["x"]);
+        assert_eq!(table.dividers[0].0, 2);
+        table.sort_by_columns(&[]);
+        assert_eq!(table.rows()[0], ownv!["a"]);
+        table.add_row(["a", "10"]);
+        table.sort_by_columns_with(&[(1, Order::Descending)], natural_cmp);
+        assert_eq!(table.rows()[0], ownv!["a", "10"]);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut table = Table::default();
+        table.add_row(["a", "1"]);
+        table.add_row(["a", "2"]);
+        table.add_spacer();
+        table.add_row_tagged(["a", "1"], &["x"]);
+        table.add_row(["b"]);
+        table.add_row(["b", "1"]);
+        table.add_row(["c"]);
+        table.dedup_rows();
+        assert_eq!(
+            table.rows(),
+            &[
+                ownv!["a", "1"],
+                ownv!["a", "2"],
+                ownv!["b"],
+                ownv!["b", "1"],
+                ownv!["c"]
+            ][..]
+        );
+        assert_eq!(table.row_tags(2), &[] as &[&str]);
+        assert_eq!(table.dividers[0].0, 2);
+        table.dedup_by_column(1);
+        assert_eq!(
+            table.rows(),
+            &[ownv!["a", "1"], ownv!["a", "2"], ownv!["b"]][..]
+        );
+        table.dedup_by_column(0);
+        assert_eq!(table.rows(), &[ownv!["a", "1"], ownv!["b"]][..]);
+        assert_eq!(table.dividers[0].0, 1);
+    }
+
+    #[test]
+    fn test_render_range() {
+        let mut table = Table::default();
+        table.set_title(["n"]);
+        table.add_section("s");
+        table.extend_rows((1..4).map(|i| row![i.to_string()]));
+        assert_eq!(table.render_range(0, 1), " n \n---\n s \n 1 \n");
+        assert_eq!(table.render_range(1, 5), " n \n---\n s \n 2 \n 3 \n");
+        assert_eq!(table.render_range(5, usize::MAX), " n \n---\n");
+    }
+
+    #[test]
+    fn test_stack_columns() {
+        let config = TableConfig::default()
+            .width(17)
+            .stack_columns(true)
+            .show_row_numbers(true);
+        let mut table = Table::new(config);
+        table.set_title(["a", "b", "c"]);
+        table.add_row(["aaaa", "bbbb", "cccc"]);
+        let should = " # | a    | b    \n---+------+------\n 1 | aaaa | bbbb \n\n # | c    \n---+------\n 1 | cccc \n";
+        assert_eq!(table.render_to_string(), should);
+        table.config.width = 40;
+        assert_eq!(table.render_to_string().lines().count(), 3);
+        table.clear_rows();
+        table.set_title(["aaaaaaaaaaaa", "bbbbbbbbbbbb", "cccccccccccc"]);
+        let rules = table
+            .render_to_string()
+            .lines()
+            .filter(|l| l.starts_with("--"))
+            .count();
+        assert_eq!(rules, 2);
+        table.config.empty = Empty::Nothing;
+        assert_eq!(table.render_to_string(), "");
+    }
+
+    #[test]
+    fn test_record_layout() {
+        let config = TableConfig::default()
+            .width(20)
+            .frame(true)
+            .show_row_numbers(true)
+            .layout(Layout::Record);
+        let mut table = Table::new(config);
+        table.set_title(["id", "comment"]);
+        table.add_row(["1", "short"]);
+        table.add_section("more");
+        table.add_row(["2", "a bit longer than that"]);
+        let should = "+-[ RECORD 1 ]-----+\n| id      | 1      |\n| comment | short  |\n| more             |\n+-[ RECORD 2 ]-----+\n| id      | 2      |\n| comment | a bit  |\n|         | longer |\n|         | than   |\n|         | that   |\n+------------------+\n";
+        assert_eq!(table.render_to_string(), should);
+        assert_eq!(table.render_range(0, 1).lines().count(), 4);
+    }
+
+    #[test]
+    fn test_right_to_left() {
+        let config = TableConfig::default()
+            .show_row_numbers(true)
+            .right_to_left(true);
+        let mut table = Table::new(config);
+        table.set_title(["a", "b"]);
+        table.add_row(["1", "2"]);
+        table.add_row(["3"]);
+        table.set_column_alignment(0, Alignment::Right);
+        let should = " b | a | # \n---+---+---\n 2 | 1 | 1 \n   | 3 | 2 \n";
+        assert_eq!(table.render_to_string(), should);
+        table.config.layout = Layout::Record;
+        let should = "-[ RECORD 1 ]-\n 1        | a \n 2        | b \n-[ RECORD 2 ]-\n 3        | a \n          | b \n";
+        assert_eq!(table.render_to_string(), should);
+    }
+
+    #[test]
+    fn test_ellipsis_marker() {
+        let config = TableConfig::default()
+            .width(17)
+            .truncate(Ellipsis::End)
+            .ellipsis_marker("...");
+        let mut table = Table::new(config);
+        table.set_title(["name", "id"]);
+        table.add_row(["abcdefghij", "0123456789"]);
+        table.set_column_ellipsis_marker(1, "~");
+        let should = " name   | id     \n--------+--------\n abc... | 01234~ \n";
+        assert_eq!(table.render_to_string(), should);
+    }
+
+    #[test]
+    fn test_trim_line_ends() {
+        let config = TableConfig::default()
+            .trim_line_ends(true)
+            .layout(Layout::Record);
+        let mut table = Table::new(config);
+        table.set_title(["name", "id"]);
+        table.add_row(["a", ""]);
+        table.add_spacer();
+        let should = "-[ RECORD 1 ]-\n name | a\n id   |\n\n";
+        assert_eq!(table.render_to_string(), should);
+        table.config.layout = Layout::Table;
+        table.force_color(true);
+        table.set_row_style_fn(|_, _| Some(Style::red()));
+        let should = " name | id\n------+----\n \x1b[31ma\x1b[0m    |\n\n";
+        assert_eq!(table.render_to_string(), should);
+    }
+
+    #[test]
+    fn test_pad_last_column() {
+        let config = TableConfig::default()
+            .width(11)
+            .frame(true)
+            .pad_last_column(false);
+        let mut table = Table::new(config);
+        table.set_title(["a", "bcde"]);
+        table.add_section("s");
+        table.add_row(["a", "b"]);
+        let should =
+            "+---+-----+\n| a | bcde|\n+---+-----+\n| s       |\n| a | b   |\n+---+-----+\n";
+        assert_eq!(table.render_to_string(), should);
+    }
+
+    #[test]
+    fn test_line_ending() {
+        let config = TableConfig::default()
+            .line_ending(LineEnding::CrLf)
+            .trim_line_ends(true)
+            .final_line_ending(false);
+        let mut table = Table::new(config);
+        table.set_title(["a", "b"]);
+        table.add_row(["c", ""]);
+        table.add_spacer();
+        assert_eq!(table.render_to_string(), " a | b\r\n---+---\r\n c |\r\n");
+        table.config.final_line_ending = true;
+        table.config.trim_line_ends = false;
+        let should = " a | b \r\n---+---\r\n c |   \r\n       \r\n";
+        assert_eq!(table.render_to_string(), should);
+    }
+
+    #[test]
+    fn test_freeze_layout() {
+        let mut first = Table::default();
+        first.add_row(["name", "x"]);
+        let mut second = Table::default();
+        second.add_row(["a", "longer"]);
+        second.freeze_layout_with(&first.column_widths());
+        assert_eq!(
+            second.to_string(),
+            " a    | l \n      | o \n      | n \n      | g \n      | e \n      | r \n"
+        );
+        second.reorder_columns(&[1]);
+        assert_eq!(second.column_widths(), vec![1, 4]);
+        second.invalidate_layout();
+        assert_eq!(second.column_widths(), vec![6, 1]);
+    }
+
+    #[test]
+    fn test_max_merge() {
+        let l = vec![1, 2, 3];
+        let r = vec![2, 0, 3, 4];
+        assert_eq!(max_merge(&l, &r), vec![2, 2, 3, 4]);
+        let l = vec![];
+        let r = vec![2, 0, 3, 4];
+        assert_eq!(max_merge(&l, &r), r);
+    }
+
+    #[test]
+    fn test_split() {
+        let cell = "foobar2000 foo";
+        assert_eq!(split(cell, 12), ownv!("foobar2000", "foo"));
+        let cell = "foobar2000    foo";
+        assert_eq!(split(cell, 12), ownv!("foobar2000", "foo"));
+        let cell = "foobar2000    foobar2000";
+        assert_eq!(split(cell, 12), ownv!("foobar2000", "foobar2000"));
+        let cell = "foobar2000     foobar2000";
+        assert_eq!(split(cell, 12), ownv!("foobar2000", "", "foobar2000"));
+        assert_eq!(split("漢字漢字", 5), ownv!("漢字", "漢字"));
+        assert_eq!(split("a漢字", 2), ownv!("a", "漢", "字"));
+        assert_eq!(split("漢", 1), ownv!("漢"));
+        assert_eq!(split("äöü", 2), ownv!("äö", "ü"));
+    }
+
+    #[test]
+    fn test_split_fuzz() {
+        const PIECES: &[&str] = &[
+            "a", "bc", " ", "  ", "日", "\u{301}", "\u{200b}", "\t", "\x1b[31m", "\u{3000}", "é",
+            "🦀", "\r\n",
+        ];
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        let mut next = |n: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % n as u64) as usize
+        };
+        for _ in 0..2000 {
+            let cell: String = (0..next(12)).map(|_| PIECES[next(PIECES.len())]).collect();
+            let w = next(6);
+            let clean = sanitize::untrusted(&cell, usize::MAX);
+            for wrapping in [Wrapping::Greedy, Wrapping::Optimal] {
+                // Control characters and escape sequences aren't measured
+                // like the terminal shows them, that's what sanitizing is for.
+                for line in lines(&clean, wrapping.split(&clean, w)) {
+                    assert!(display_width(line) <= cmp::max(w, 2), "{:?} {}", cell, w);
+                }
+                let joined: String = lines(&cell, wrapping.split(&cell, w))
+                    .concat()
+                    .chars()
+                    .filter(|c| !c.is_whitespace())
+                    .collect();
+                let content: String = cell.chars().filter(|c| !c.is_whitespace()).collect();
+                assert_eq!(joined, content, "{:?} {}", cell, w);
+            }
+            let mut table = Table::new(TableConfig::default().width(w * 3).sanitized(w));
+            table.add_row([&cell[..], &cell]);
+            table.to_string();
+        }
+    }
+
+    #[test]
+    fn test_child_rows() {
+        let mut table = Table::new(TableConfig::default().tree_indent(2));
+        table.add_row(ownv!["a"]);
+        table.add_row_tagged(ownv!["b"], &["tag"]);
+        table.add_spacer();
+        table.add_row(ownv!["c"]);
+        let child = table.add_child_row(0, ownv!["d"]);
+        assert_eq!(child, 1);
+        table.add_child_row(child, ownv!["e"]);
+        table.add_child_row(3, ownv!["f"]);
+        assert_eq!(table.row_tags(3), ["tag"]);
+        let should = " a     \n └ d   \n   └ e \n b     \n └ f   \n       \n c     \n";
+        assert_eq!(format!("{}", table), should);
+
+        let mut table = Table::new(TableConfig::default().tree_indent(2).width(8));
+        table.add_row(ownv!["  a"]);
+        let child = table.add_child_row(0, ownv!["d"]);
+        table.add_child_row(child, ownv!["e f"]);
+        assert_eq!(
+            format!("{}", table),
+            " a      \n └ d    \n   └ e  \n f      \n"
+        );
+    }
+
+    #[test]
+    fn test_multi_line_cells() {
+        let config = TableConfig::default();
+        let cell = "a\r\n\n  b c";
+        assert_eq!(lines(cell, config.split(cell, 3)), ["a", "", "b", "c"]);
+        assert_eq!(
+            lines(cell, config.split_indented(cell, 4)),
+            ["a", "", "  b", "c"]
+        );
+        assert_eq!(cell_width("ab\nabcd\n"), 4);
+        let mut table = Table::default();
+        table.add_row(ownv!["a\nbc", "d"]);
+        assert_eq!(format!("{}", table), " a  | d \n bc |   \n");
+    }
+
+    #[test]
+    fn test_styled_nested_table() {
+        let mut inner = Table::default();
+        inner.force_color(true);
+        inner.highlight("needle", Style::yellow().bold());
+        inner.add_row(ownv!["needle", "x"]);
+        let mut table = Table::new(TableConfig::default().width(30));
+        table.add_cells(vec![Cell::new("a"), Cell::table(inner)]);
+        table.force_color(true);
+        let out = format!("{}", table);
+        assert_eq!(out, " a |  \x1b[1;33mneedle\x1b[0m | x  \n");
+        table.force_color(false);
+        assert_eq!(format!("{}", table), " a |  needle | x  \n");
+    }
+
+    #[test]
+    fn test_plain_nested_table() {
+        let mut inner = Table::default();
+        inner.force_color(true);
+        inner.add_row(ownv!["\x1b[31mred\x1b[0m"]);
+        inner.highlight("red", Style::yellow());
+        let mut table = Table::default();
+        table.force_color(true);
+        table.add_cells(vec![Cell::table(inner)]);
+        assert_eq!(table.render_plain(), "  red  \n");
+    }
+
+    #[test]
+    #[should_panic(expected = "no body row 1")]
+    fn test_child_row_without_parent() {
+        let mut table = Table::default();
+        table.add_row(ownv!["a"]);
+        table.add_child_row(1, ownv!["b"]);
+    }
+
+    #[test]
+    fn test_columns_window() {
+        let mut table = Table::new(TableConfig::default().key_column(1));
+        table.add_row(ownv!["a", "b", "c", "d"]);
+        assert_eq!(table.render_columns_window(0, 2), " b | a | c \n");
+        assert_eq!(table.render_columns_window(2, 5), " b | d \n");
+        assert_eq!(table.render_columns_window(9, 1), " b \n");
+        let mut table = Table::default();
+        table.add_row(ownv!["a", "b", "c"]);
+        assert_eq!(table.render_columns_window(1, 1), " b \n");
+    }
+
+    #[test]
+    fn test_background_fill() {
+        let mut table = Table::default();
+        table.force_color(true);
+        table.add_cells(vec![
+            Cell::new("ab").style(Style::red().on(Color::White).bold()),
+            Cell::new("c"),
+        ]);
+        table.add_row(ownv!["d", "e"]);
+        let out = format!("{}", table);
+        let (on, reset) = ("\x1b[47m", "\x1b[0m");
+        let text = "\x1b[1;31;47mab";
+        let should = format!(
+            "{} {}{}{}{} {}| c \n d  | e \n",
+            on, reset, text, reset, on, reset
+        );
+        assert_eq!(out, should);
+    }
+
+    #[test]
+    fn test_merge_cells() {
+        let config = TableConfig::default().merge_cells(true).frame(true);
+        let mut table = Table::new(config);
+        table.add_row(ownv!["ab", "ab", "c"]);
+        table.add_row(ownv!["", "", "d"]);
+        let should = "\
++----+----+---+
+|   ab    | c |
+|    |    | d |
++----+----+---+
+";
+        assert_eq!(format!("{}", table), should);
+        let config = TableConfig::default()
+            .merge_cells(true)
+            .show_row_numbers(true);
+        let mut table = Table::new(config);
+        table.add_row(ownv!["1", "1", "2"]);
+        assert_eq!(format!("{}", table), " 1 |   1   | 2 \n");
+    }
+
+    #[test]
+    fn test_continuation() {
+        let config = TableConfig::default()
+            .width(16)
+            .continuation(Continuation::Suffix(" \\"));
+        let mut table = Table::new(config);
+        table.add_row(["ls", "-l /usr/local/bin"]);
+        table.add_row(["a", "b"]);
+        let should = " ls | -l \\      \n    | /usr/lo \\ \n    | cal/bin   \n a  | b         \n";
+        assert_eq!(table.to_string(), should);
+        table.prepare();
+        assert_eq!(table.to_string(), should);
+    }
+
+    #[test]
+    fn test_wide_chars() {
+        let mut table = Table::with_width(11);
+        table.add_row(ownv!["a", "漢字漢字"]);
+        let out = format!("{}", table);
+        let should = "\
+# a | 漢字  #
+#   | 漢字  #
+"
+        .replace("#", "");
+        assert_eq!(out, should);
+    }
+
+    #[test]
+    fn test_distribute() {
+        let dims = vec![10, 5, 20, 15];
+        let dis = distribute(&dims, 40, 0, 1);
+        assert_eq!(dis, vec![10, 5, 11, 11]);
+        assert_eq!(distribute(&dims, 11, 1, 1), vec![1, 1, 1]);
+        assert_eq!(distribute(&dims, 10, 1, 1), vec![3, 2]);
+        assert_eq!(distribute(&dims, 2, 1, 1), vec![1]);
+        assert_eq!(distribute(&dims, 0, 5, 1), vec![1]);
+        assert_eq!(distribute(&[], 0, 0, 1), vec![]);
+        assert_eq!(distribute(&dims, 40, 0, 2), vec![9, 5, 10, 10]);
+        assert_eq!(distribute(&dims, 7, 0, 2), vec![1, 1, 1]);
+        assert_eq!(distribute(&dims, 4, 0, 0), vec![1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_borderless() {
+        let mut table = Table::new(TableConfig::borderless().width(20));
+        table.set_title(ownv!["name", "image", "status"]);
+        table.add_row(ownv!["web-7d4b9", "nginx:1.25-alpine", "CrashLoopBackOff"]);
+        let out = format!("{}", table);
+        assert!(out.lines().all(|l| display_width(l) <= 20), "{}", out);
+        assert_eq!(out.lines().next(), Some("name   image   statu"));
+        assert!(out.lines().nth(2).unwrap().starts_with("web-7"), "{}", out);
+    }
+
+    #[test]
+    fn test_wide_separators() {
+        let border = BorderChars {
+            horizontal: "-",
+            vertical: "||",
+            cross: "++",
+            top_left: "++",
+            top_right: "++",
+            bottom_left: "++",
+            bottom_right: "++",
+            top_junction: "++",
+            bottom_junction: "++",
+            left_junction: "++",
+            right_junction: "++",
+        };
+        for &arrangement in &[
+            ContentArrangement::Dynamic,
+            ContentArrangement::DynamicFullWidth,
+            ContentArrangement::MinimizeHeight,
+        ] {
+            let config = TableConfig::default()
+                .width(30)
+                .border(border)
+                .frame(true)
+                .arrangement(arrangement);
+            assert!(config.validate(3).is_ok());
+            let mut table = Table::new(config);
+            table.add_row(ownv!["a long first cell", "second cell", "third"]);
+            let out = format!("{}", table);
+            assert!(out.lines().all(|l| display_width(l) <= 30), "{}", out);
+            if arrangement == ContentArrangement::DynamicFullWidth {
+                assert!(out.lines().all(|l| display_width(l) == 30), "{}", out);
+            }
+        }
+        // Two columns of one character with padding, a separator and frame.
+        let config = TableConfig::default().border(border).frame(true);
+        assert!(config.width(12).validate(2).is_ok());
+        let config = TableConfig::default().border(border).frame(true);
+        assert!(config.width(11).validate(2).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let config = TableConfig::default().width(7);
+        assert_eq!(config.validate(0), Ok(()));
+        assert_eq!(config.validate(2), Ok(()));
+        assert!(config.validate(3).is_err());
+        let config = config.width(8).frame(true).pad_last_column(false);
+        assert_eq!(config.validate(2), Ok(()));
+        let config = config.show_row_numbers(true).key_column(1);
+        assert_eq!(
+            config.validate(1),
+            Err(ConfigError::KeyColumn { idx: 1, columns: 1 })
+        );
+        assert_eq!(
+            config.validate(2),
+            Err(ConfigError::TooNarrow {
+                width: 8,
+                columns: 3,
+                padding: 1
+            })
+        );
+        let config = TableConfig::default()
+            .width(0)
+            .arrangement(ContentArrangement::Disabled);
+        assert_eq!(config.validate(5), Ok(()));
+    }
+
+    #[test]
+    fn test_degenerate_width() {
+        let mut table = Table::new(TableConfig::default().width(5).padding(2));
+        table.set_title(["who", "what"]);
+        table.add_row(["da", "foobar"]);
+        assert_eq!(
+            table.to_string(),
+            "  w  \n  h  \n  o  \n-----\n  d  \n  a  \n"
+        );
+        let mut table = Table::new(TableConfig::default().width(0).frame(true));
+        table.add_row(["a", "b"]);
+        assert_eq!(table.to_string(), "+---+\n| a |\n+---+\n");
+    }
+    #[test]
+    fn test_wrapping() {
+        let mut table = Table::with_width(20);
+        table.add_row(ownv!["da", "foobar foobar", "bar"]);
+        table.add_row(ownv!["da", "foobar!", "bar"]);
+        let out = format!("{}", table);
+        let should = "\
+# da | foobar  | bar #
+#    | foobar  |     #
+# da | foobar! | bar #
+"
+            .replace("#", "");
+        assert_eq!(out, should);
+    }
+
+    #[test]
+    fn test_row_style_fn() {
+        let mut table = Table::with_width(40);
+        table.add_row(ownv!["ok", "a"]);
+        table.add_row(ownv!["FAILED", ""]);
+        table.force_color(true);
+        table.set_row_style_fn(|i, row| {
+            if row[0] == "FAILED" {
+                assert_eq!(i, 1);
+                Some(Style::red())
+            } else {
+                None
+            }
+        });
+        let out = format!("{}", table);
+        let should = " ok     | a \n \x1b[31mFAILED\x1b[0m |   \n";
+        assert_eq!(out, should);
+    }
+
+    #[test]
+    fn test_rules() {
+        let mut table = Table::with_width(40);
+        table.add_row(ownv!["cpu", "95"]);
+        table.add_row(ownv!["mem", "40"]);
+        table.set_row_style_fn(|_, _| Some(Style::blue()));
+        table.add_rule(Rule::column(1).gt(90.0).style(Style::red()));
+        table.force_color(true);
+        let out = format!("{}", table);
+        let should = "\
+# \x1b[34mcpu\x1b[0m | \x1b[31m95\x1b[0m #
+# \x1b[34mmem\x1b[0m | \x1b[34m40\x1b[0m #
+"
+        .replace("#", "");
+        assert_eq!(out, should);
+        table.force_color(false);
+        assert_eq!(format!("{}", table), " cpu | 95 \n mem | 40 \n");
+        table.force_color(true);
+        table.add_row(ownv!["\x1b[1mio\x1b[0m", "1"]);
+        assert_eq!(table.render_plain(), " cpu | 95 \n mem | 40 \n io  | 1  \n");
+    }
+
+    #[test]
+    fn test_frame() {
+        let config = TableConfig::default()
+            .width(20)
+            .border(BorderChars::unicode())
+            .frame(true);
+        let mut table = Table::new(config);
+        table.set_title(ownv!["who", "what"]);
+        table.add_row(ownv!["da", "foobar foobar"]);
+        let out = format!("{}", table);
+        let should = "\
+┌─────┬────────────┐
+│ who │ what       │
+├─────┼────────────┤
+│ da  │ foobar     │
+│     │ foobar     │
+└─────┴────────────┘
+";
+        assert_eq!(out, should);
+    }
+
+    #[test]
+    fn test_arrangement() {
+        let mut table = Table::new(
+            TableConfig::default()
+                .width(10)
+                .arrangement(ContentArrangement::Disabled),
+        );
+        table.add_row(ownv!["da", "foobar foobar"]);
+        assert_eq!(format!("{}", table), " da | foobar foobar \n");
+        let mut table = Table::new(
+            TableConfig::default()
+                .width(20)
+                .arrangement(ContentArrangement::DynamicFullWidth),
+        );
+        table.add_row(ownv!["da", "foo"]);
+        assert_eq!(table.column_widths(), vec![7, 8]);
+        assert_eq!(stretch(&[1, 1, 1], 15, 1, 1), vec![3, 2, 2]);
+        assert_eq!(stretch(&[1, 1, 1], 16, 1, 3), vec![2, 1, 1]);
+        assert_eq!(stretch(&[1, 1, 1], 15, 0, 0), vec![5, 5, 5]);
+        let mut table = Table::new(TableConfig::default().width(24));
+        table.add_row(["aaaaaaaaaaaa bbbbbbbbbbbb", "c d e f g h i j k l"]);
+        assert_eq!(table.measure().1, 4);
+        table.config.arrangement = ContentArrangement::MinimizeHeight;
+        assert_eq!(table.column_widths(), vec![12, 7]);
+        assert_eq!(table.measure().1, 3);
+    }
+
+    #[test]
+    fn test_control_chars() {
+        let mut table = Table::new(TableConfig::default().control_chars(ControlChars::Replace));
+        table.set_title(ownv!["a\tb"]);
+        table.add_row(ownv!["ring\x07"]);
+        assert_eq!(format!("{}", table), " a␉b   \n-------\n ring␇ \n");
+    }
+
+    #[cfg(feature = "terminal_size")]
+    #[test]
+    fn test_fit_terminal() {
+        let mut table = Table::with_width(20);
+        table.fit_terminal();
+        let expected = terminal_width().unwrap_or(20);
+        assert_eq!(table.width(), expected);
+    }
+
+    #[test]
+    fn test_render_to_string() {
+        let mut table = Table::default();
+        table.add_row(ownv!["a", "b"]);
+        assert_eq!(table.render_to_string(), format!("{}", table));
+    }
+
+    #[test]
+    fn test_prepare() {
+        let mut table = Table::with_width(20);
+        table.set_column_format(2, Format::Currency("USD"));
+        table.add_row(ownv!["da", "foobar foobar", "1"]);
+        let plain = format!("{}", table);
+        table.prepare();
+        assert!(table.prepared().is_some());
+        assert_eq!(format!("{}", table), plain);
+        table.add_row(ownv!["da", "foobar!", "2"]);
+        assert!(table.prepared().is_none());
+    }
+
+    #[test]
+    fn test_prepare_changing_format() {
+        let calls = AtomicUsize::new(0);
+        let mut table = Table::with_width(20);
+        table.set_column_formatter(0, |cell: &str| {
+            let n = calls.fetch_add(1, AtomicOrdering::SeqCst);
+            format!("{} {}", cell.repeat(8 - n), n)
+        });
+        table.add_row(ownv!["ab"]);
+        table.prepare();
+        let out = format!("{}", table);
+        assert!(out.contains(" 1 "), "{}", out);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_prepare_relative() {
+        let soon = chrono::Utc::now().timestamp() + 10;
+        let mut table = Table::with_width(40);
+        table.set_column_format(0, Format::Relative { now: None });
+        table.add_row(vec![Cow::Owned(soon.to_string())]);
+        table.prepare();
+        assert!(table.prepared().is_none());
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        let out = format!("{}", table);
+        assert!(out.contains(" seconds") && !out.contains("10"), "{}", out);
+    }
+
+    #[test]
+    fn test_column_wrapper() {
+        let mut table = Table::with_width(20);
+        table.add_row(ownv!["da", "a,b,c"]);
+        table.set_column_wrapper(1, |cell: &str, _| {
+            cell.split(',').map(str::to_owned).collect()
+        });
+        let wrapped = format!("{}", table);
+        assert_eq!(wrapped.lines().count(), 3);
+        table.prepare();
+        assert_eq!(format!("{}", table), wrapped);
+        let mut records = Table::new(TableConfig::default().layout(Layout::Record));
+        records.set_title(ownv!["n", "list"]);
+        records.add_row(ownv!["1", "a,b"]);
+        records.set_column_wrapper(1, |cell: &str, _| {
+            cell.split(',').map(str::to_owned).collect()
+        });
+        assert!(!records.to_string().contains(','));
+    }
+
+    #[test]
+    fn test_column_formatter() {
+        let mut table = Table::default();
+        table.add_row(ownv!["3.5"]);
+        table.set_column_format(0, Format::Currency("USD"));
+        table.set_column_formatter(0, |cell: &str| format!("{} each", cell));
+        assert_eq!(table.formatted_rows()[0][0], "$3.50 each");
+        assert_eq!(table.rows()[0][0], "3.5");
+    }
+
+    #[test]
+    fn test_suppress_repeats() {
+        let mut table = Table::with_width(20);
+        table.add_row(ownv!["a", "x"]);
+        table.add_row(ownv!["b", "x"]);
+        table.add_row(ownv!["a", "x"]);
+        table.add_row(ownv!["a", "y"]);
+        table.set_column_suppress_repeats(0, "");
+        table.set_column_suppress_repeats(1, "");
+        let plain = format!("{}", table);
+        assert_eq!(plain, " a | x \n b |   \n a |   \n   | y \n");
+        table.prepare();
+        assert_eq!(format!("{}", table), plain);
+        let sorted = table.view().sort_by_columns(&[(0, Order::Ascending)]);
+        assert_eq!(sorted.to_string(), " a | x \n   |   \n   | y \n b | x \n");
+    }
+
+    #[test]
+    fn test_column_format() {
+        let mut table = Table::with_width(40);
+        table.set_title(ownv!["item", "price"]);
+        table.add_row(ownv!["tea", "3.5"]);
+        table.add_row(ownv!["pot", "-1234"]);
+        table.set_column_format(1, Format::Currency("USD"));
+        let out = format!("{}", table);
+        let should = "\
+# item |       price #
+#------+-------------#
+# tea  |       $3.50 #
+# pot  | ($1,234.00) #
+"
+        .replace("#", "");
+        assert_eq!(out, should);
+    }
+
+    #[test]
+    fn test_plain_tsv_unwrapped() {
+        let config = TableConfig::default()
+            .width(12)
+            .truncate(Ellipsis::default());
+        let mut table = Table::new(config);
+        table.set_title(ownv!["host", "message"]);
+        table.add_row(ownv!["web-1", "connection refused\nretrying"]);
+        table.add_row(ownv!["db"]);
+        assert!(table.to_string().lines().count() > 3);
+        assert_eq!(
+            table.to_plain_tsv(),
+            "host\tmessage\nweb-1\tconnection refused retrying\ndb\n"
+        );
+    }
 }
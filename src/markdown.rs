@@ -0,0 +1,195 @@
+//! Markdown pipe tables.
+use std::borrow::Cow;
+use std::fmt;
+
+use style::strip_ansi;
+use {Alignment, Renderer, Row, Table};
+
+/// GitHub flavored Markdown pipe tables.
+///
+/// The title becomes the header row, a table without title gets an empty
+/// one. Escape sequences are stripped from the cells.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MarkdownRenderer;
+
+impl Renderer for MarkdownRenderer {
+    fn render(&self, table: &Table, f: &mut dyn fmt::Write) -> fmt::Result {
+        let columns = table.column_count();
+        if columns == 0 {
+            return Ok(());
+        }
+        let title = table.title().unwrap_or(&[]);
+        fmt_line(title, columns, f)?;
+        for idx in 0..columns {
+            f.write_str(match table.configured_alignment(idx) {
+                Some(Alignment::Left) => "|:---",
+                Some(Alignment::Center) => "|:---:",
+                Some(Alignment::Right) => "|---:",
+                None => "|---",
+            })?;
+        }
+        f.write_str("|\n")?;
+        for row in table.formatted_rows() {
+            fmt_line(&row, columns, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write a table line of `columns` cells, missing cells are empty.
+fn fmt_line<S: AsRef<str>>(row: &[S], columns: usize, f: &mut dyn fmt::Write) -> fmt::Result {
+    for idx in 0..columns {
+        let cell = row.get(idx).map_or("", |c| c.as_ref());
+        write!(f, "| {} ", escape(&strip_ansi(cell)))?;
+    }
+    f.write_str("|\n")
+}
+
+/// `s` with pipes escaped and line breaks as `<br>`.
+fn escape(s: &str) -> Cow<'_, str> {
+    if !s.contains(['|', '\n']) {
+        return Cow::Borrowed(s);
+    }
+    let lines: Vec<String> = s
+        .lines()
+        .map(|l| l.trim_end_matches('\r').replace('|', "\\|"))
+        .collect();
+    Cow::Owned(lines.join("<br>"))
+}
+
+/// The title, column alignments and body rows of the first pipe table in
+/// `text`.
+pub(crate) fn parse(text: &str) -> Option<(Row<'_>, Vec<Option<Alignment>>, Vec<Row<'_>>)> {
+    let mut lines = text.lines().peekable();
+    while let Some(line) = lines.next() {
+        let title = match cells(line) {
+            Some(title) => title,
+            None => continue,
+        };
+        let alignments = match lines.peek().and_then(|l| delimiter(l)) {
+            Some(alignments) => alignments,
+            None => continue,
+        };
+        lines.next();
+        let rows = lines.map_while(cells).collect();
+        return Some((title, alignments, rows));
+    }
+    None
+}
+
+/// The cells of a table line, `None` if it isn't one.
+fn cells(line: &str) -> Option<Row<'_>> {
+    let line = line.trim();
+    if !line.contains('|') {
+        return None;
+    }
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = match line.strip_suffix('|') {
+        Some(rest) if !rest.ends_with('\\') => rest,
+        _ => line,
+    };
+    let mut cells = vec![];
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '|' if !escaped => {
+                cells.push(cell(&line[start..i]));
+                start = i + 1;
+            }
+            _ => escaped = c == '\\' && !escaped,
+        }
+    }
+    cells.push(cell(&line[start..]));
+    Some(cells)
+}
+
+/// A trimmed cell with escaped pipes unescaped.
+fn cell(s: &str) -> Cow<'_, str> {
+    let s = s.trim();
+    if s.contains("\\|") {
+        Cow::Owned(s.replace("\\|", "|"))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+/// The alignments of a delimiter line like `|:--|--:|`, `None` if it isn't
+/// one.
+fn delimiter(line: &str) -> Option<Vec<Option<Alignment>>> {
+    cells(line)?
+        .iter()
+        .map(|c| {
+            let left = c.starts_with(':');
+            let right = c.len() > 1 && c.ends_with(':');
+            let dashes = c.trim_start_matches(':').trim_end_matches(':');
+            if dashes.is_empty() || !dashes.bytes().all(|b| b == b'-') {
+                return None;
+            }
+            Some(match (left, right) {
+                (true, true) => Some(Alignment::Center),
+                (false, true) => Some(Alignment::Right),
+                (true, false) => Some(Alignment::Left),
+                (false, false) => None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cells() {
+        assert_eq!(cells("| a | b |").unwrap(), ["a", "b"]);
+        assert_eq!(cells("a|b").unwrap(), ["a", "b"]);
+        assert_eq!(cells(r"| a \| b | c |").unwrap(), ["a | b", "c"]);
+        assert_eq!(cells("| a | |").unwrap(), ["a", ""]);
+        assert_eq!(cells("no table"), None);
+    }
+
+    #[test]
+    fn test_delimiter() {
+        assert_eq!(
+            delimiter("|:--|--:|:-:|---|").unwrap(),
+            [
+                Some(Alignment::Left),
+                Some(Alignment::Right),
+                Some(Alignment::Center),
+                None
+            ]
+        );
+        assert_eq!(delimiter("| a | - |"), None);
+        assert_eq!(delimiter("| : |"), None);
+    }
+
+    #[test]
+    fn test_render_round_trip() {
+        let mut table = Table::default();
+        table.set_title(vec!["left", "center", "right", "none"]);
+        table.add_row(vec!["a|b", "two\nlines", "3"]);
+        table.set_column_alignment(0, Alignment::Left);
+        table.set_column_alignment(1, Alignment::Center);
+        table.set_column_alignment(2, Alignment::Right);
+        let text = table.to_markdown();
+        assert_eq!(
+            text,
+            "| left | center | right | none |\n\
+             |:---|:---:|---:|---|\n\
+             | a\\|b | two<br>lines | 3 |  |\n"
+        );
+        let (title, alignments, rows) = parse(&text).unwrap();
+        assert_eq!(title, ["left", "center", "right", "none"]);
+        assert_eq!(
+            alignments,
+            [
+                Some(Alignment::Left),
+                Some(Alignment::Center),
+                Some(Alignment::Right),
+                None
+            ]
+        );
+        assert_eq!(rows, [["a|b", "two<br>lines", "3", ""]]);
+    }
+}
@@ -0,0 +1,88 @@
+//! Wrapping with the least raggedness.
+use std::ops::Range;
+
+use display_width;
+use unicode_width::UnicodeWidthChar;
+
+/// The byte ranges of `cell` wrapped into lines of at most `w` columns with
+/// the least raggedness.
+///
+/// Lines are broken at spaces so that the sum of the squared free space of
+/// all lines but the last is minimal. Words wider than `w` are broken up.
+pub(crate) fn split(cell: &str, w: usize) -> Vec<Range<usize>> {
+    let words = words(cell, w);
+    let n = words.len();
+    // The least cost of the lines starting with word `i` and where the
+    // line starting with it ends.
+    let mut cost = vec![0u64; n + 1];
+    let mut next = vec![n; n + 1];
+    for i in (0..n).rev() {
+        cost[i] = u64::MAX;
+        for j in i + 1..=n {
+            let width = display_width(&cell[words[i].start..words[j - 1].end]);
+            if width > w && j > i + 1 {
+                break;
+            }
+            let free = w.saturating_sub(width) as u64;
+            let line = if j == n { 0 } else { free.saturating_mul(free) };
+            let total = line.saturating_add(cost[j]);
+            if total < cost[i] {
+                cost[i] = total;
+                next[i] = j;
+            }
+        }
+    }
+    let mut lines = vec![];
+    let mut i = 0;
+    while i < n {
+        lines.push(words[i].start..words[next[i] - 1].end);
+        i = next[i];
+    }
+    lines
+}
+
+/// Byte ranges of the words of `cell`, words wider than `w` broken into
+/// pieces.
+fn words(cell: &str, w: usize) -> Vec<Range<usize>> {
+    let mut words = vec![];
+    let mut start = 0;
+    for word in cell.split(' ') {
+        let end = start + word.len();
+        let mut piece = start;
+        let mut width = 0;
+        for (i, c) in word.char_indices() {
+            let c_width = c.width().unwrap_or(0);
+            if width + c_width > w && start + i > piece {
+                words.push(piece..start + i);
+                piece = start + i;
+                width = 0;
+            }
+            width += c_width;
+        }
+        if end > piece {
+            words.push(piece..end);
+        }
+        start = end + 1;
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    fn split(cell: &str, w: usize) -> Vec<&str> {
+        super::split(cell, w)
+            .into_iter()
+            .map(|r| &cell[r])
+            .collect()
+    }
+
+    #[test]
+    fn test_split() {
+        // Greedy: "aaa bb" / "cc" / "dddddd"
+        assert_eq!(split("aaa bb cc dddddd", 6), ["aaa", "bb cc", "dddddd"]);
+        assert_eq!(split("  a  b ", 4), ["a  b"]);
+        assert_eq!(split("abcdefg", 3), ["abc", "def", "g"]);
+        assert_eq!(split("日本語", 3), ["日", "本", "語"]);
+        assert_eq!(split("", 3), Vec::<&str>::new());
+    }
+}
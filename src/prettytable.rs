@@ -0,0 +1,279 @@
+//! Formats in the shape of [prettytable](https://github.com/phsym/prettytable-rs)'s
+//! `format` module, to ease migrating from prettytable.
+//!
+//! ```
+//! use lazytable::prettytable::{FormatBuilder, LinePosition, LineSeparator};
+//! use lazytable::Table;
+//!
+//! let format = FormatBuilder::new()
+//!     .column_separator('|')
+//!     .borders('|')
+//!     .separators(
+//!         &[LinePosition::Top, LinePosition::Bottom],
+//!         LineSeparator::new('-', '+', '+', '+'),
+//!     )
+//!     .padding(1, 1)
+//!     .build();
+//! let table = Table::from_format(&format);
+//! ```
+//!
+//! lazytable can't express everything prettytable can:
+//! * Separators between body rows (`LinePosition::Intern`) are ignored.
+//! * Any border or top/bottom separator turns on the full frame, and the
+//!   side edges are drawn with the column separator.
+//! * Padding is the same on both sides, the larger one is used.
+//! * The top and bottom lines share the horizontal character of the top
+//!   separator (or the bottom separator if there is no top separator).
+use {BorderChars, TableConfig};
+
+/// Position of a line separator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LinePosition {
+    Top,
+    Title,
+    Intern,
+    Bottom,
+}
+
+/// Characters of a line separator.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LineSeparator {
+    line: String,
+    junc: String,
+    ljunc: String,
+    rjunc: String,
+}
+
+impl LineSeparator {
+    /// A separator drawn with `line`, crossing column separators with `junc`
+    /// and the left and right border with `ljunc` and `rjunc`.
+    pub fn new(line: char, junc: char, ljunc: char, rjunc: char) -> LineSeparator {
+        LineSeparator {
+            line: line.to_string(),
+            junc: junc.to_string(),
+            ljunc: ljunc.to_string(),
+            rjunc: rjunc.to_string(),
+        }
+    }
+}
+
+impl Default for LineSeparator {
+    fn default() -> LineSeparator {
+        LineSeparator::new('-', '+', '+', '+')
+    }
+}
+
+/// Table formatting rules, convertible into a `TableConfig`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TableFormat {
+    csep: Option<String>,
+    lborder: Option<String>,
+    rborder: Option<String>,
+    lsep: Option<LineSeparator>,
+    tsep: Option<LineSeparator>,
+    top_sep: Option<LineSeparator>,
+    bottom_sep: Option<LineSeparator>,
+    pad_left: usize,
+    pad_right: usize,
+}
+
+impl TableFormat {
+    /// A format without any separators or padding.
+    pub fn new() -> TableFormat {
+        TableFormat::default()
+    }
+
+    /// The left and right padding.
+    pub fn get_padding(&self) -> (usize, usize) {
+        (self.pad_left, self.pad_right)
+    }
+
+    pub fn padding(&mut self, left: usize, right: usize) {
+        self.pad_left = left;
+        self.pad_right = right;
+    }
+
+    pub fn column_separator(&mut self, separator: char) {
+        self.csep = Some(separator.to_string());
+    }
+
+    pub fn borders(&mut self, border: char) {
+        self.left_border(border);
+        self.right_border(border);
+    }
+
+    pub fn left_border(&mut self, border: char) {
+        self.lborder = Some(border.to_string());
+    }
+
+    pub fn right_border(&mut self, border: char) {
+        self.rborder = Some(border.to_string());
+    }
+
+    pub fn separator(&mut self, what: LinePosition, separator: LineSeparator) {
+        let sep = Some(separator);
+        match what {
+            LinePosition::Top => self.top_sep = sep,
+            LinePosition::Title => self.tsep = sep,
+            LinePosition::Intern => self.lsep = sep,
+            LinePosition::Bottom => self.bottom_sep = sep,
+        }
+    }
+
+    pub fn separators(&mut self, what: &[LinePosition], separator: LineSeparator) {
+        for pos in what {
+            self.separator(*pos, separator.clone());
+        }
+    }
+}
+
+/// Builder for `TableFormat`.
+#[derive(Clone, Debug, Default)]
+pub struct FormatBuilder {
+    format: TableFormat,
+}
+
+impl FormatBuilder {
+    pub fn new() -> FormatBuilder {
+        FormatBuilder::default()
+    }
+
+    pub fn padding(mut self, left: usize, right: usize) -> FormatBuilder {
+        self.format.padding(left, right);
+        self
+    }
+
+    pub fn column_separator(mut self, separator: char) -> FormatBuilder {
+        self.format.column_separator(separator);
+        self
+    }
+
+    pub fn borders(mut self, border: char) -> FormatBuilder {
+        self.format.borders(border);
+        self
+    }
+
+    pub fn left_border(mut self, border: char) -> FormatBuilder {
+        self.format.left_border(border);
+        self
+    }
+
+    pub fn right_border(mut self, border: char) -> FormatBuilder {
+        self.format.right_border(border);
+        self
+    }
+
+    pub fn separator(mut self, what: LinePosition, separator: LineSeparator) -> FormatBuilder {
+        self.format.separator(what, separator);
+        self
+    }
+
+    pub fn separators(mut self, what: &[LinePosition], separator: LineSeparator) -> FormatBuilder {
+        self.format.separators(what, separator);
+        self
+    }
+
+    pub fn build(&self) -> TableFormat {
+        self.format.clone()
+    }
+}
+
+impl<'a> From<&'a TableFormat> for TableConfig<'a> {
+    fn from(format: &'a TableFormat) -> TableConfig<'a> {
+        let mut border = BorderChars::ascii();
+        border.vertical = format.csep.as_ref().map_or("", |s| s.as_str());
+        let outer = format.top_sep.as_ref().or(format.bottom_sep.as_ref());
+        if let Some(sep) = outer.or(format.tsep.as_ref()) {
+            border.horizontal = &sep.line;
+        }
+        if let Some(ref sep) = format.tsep {
+            border.cross = &sep.junc;
+            border.left_junction = &sep.ljunc;
+            border.right_junction = &sep.rjunc;
+        }
+        if let Some(ref sep) = format.top_sep {
+            border.top_junction = &sep.junc;
+            border.top_left = &sep.ljunc;
+            border.top_right = &sep.rjunc;
+        }
+        if let Some(ref sep) = format.bottom_sep {
+            border.bottom_junction = &sep.junc;
+            border.bottom_left = &sep.ljunc;
+            border.bottom_right = &sep.rjunc;
+        }
+        let frame = format.lborder.is_some()
+            || format.rborder.is_some()
+            || format.top_sep.is_some()
+            || format.bottom_sep.is_some();
+        let config = TableConfig::default()
+            .padding(format.pad_left.max(format.pad_right))
+            .border(border)
+            .frame(frame);
+        match format.tsep {
+            Some(ref sep) => config.title_rule(&sep.line),
+            None => config,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Table;
+
+    #[test]
+    fn test_box_chars() {
+        let format = FormatBuilder::new()
+            .column_separator('│')
+            .borders('│')
+            .separators(&[LinePosition::Top], LineSeparator::new('─', '┬', '┌', '┐'))
+            .separators(
+                &[LinePosition::Title],
+                LineSeparator::new('─', '┼', '├', '┤'),
+            )
+            .separators(
+                &[LinePosition::Bottom],
+                LineSeparator::new('─', '┴', '└', '┘'),
+            )
+            .padding(1, 1)
+            .build();
+        let config = TableConfig::from(&format);
+        assert_eq!(config.border, BorderChars::unicode());
+        assert!(config.frame);
+        let mut table = Table::from_format(&format);
+        table.set_title(["a", "b"]);
+        table.add_row(["c", "d"]);
+        let should = "\
+┌───┬───┐
+│ a │ b │
+├───┼───┤
+│ c │ d │
+└───┴───┘
+";
+        assert_eq!(format!("{}", table), should);
+    }
+
+    #[test]
+    fn test_title_separator() {
+        let format = FormatBuilder::new()
+            .column_separator('|')
+            .separators(
+                &[LinePosition::Title],
+                LineSeparator::new('=', '+', '+', '+'),
+            )
+            .separators(&[LinePosition::Bottom], LineSeparator::default())
+            .padding(1, 1)
+            .build();
+        let mut table = Table::from_format(&format);
+        table.set_title(["a", "b"]);
+        table.add_row(["c", "d"]);
+        let should = "\
++---+---+
+| a | b |
++===+===+
+| c | d |
++---+---+
+";
+        assert_eq!(table.render_plain(), should);
+    }
+}
@@ -0,0 +1,1072 @@
+//! Turning a `Table` into text.
+use std::borrow::Cow;
+use std::cmp;
+use std::fmt;
+use std::iter::Peekable;
+use std::ops::Range;
+use std::slice;
+
+use highlight::marks;
+use style::strip_ansi;
+use truncate::truncate;
+use {
+    cell_width, display_width, wrap, Alignment, CellBorders, ContentArrangement, Continuation,
+    Divider, Ellipsis, Empty, Layout, Line, LineEnding, RuleChars, Style, Table,
+};
+
+/// An output format for tables.
+///
+/// `Table` implements `Display` with `TextRenderer`. Implement this trait to
+/// add other formats without touching the table itself:
+///
+/// ```
+/// use std::fmt;
+/// use lazytable::{Renderer, Table};
+///
+/// struct Csv;
+///
+/// impl Renderer for Csv {
+///     fn render(&self, table: &Table, f: &mut dyn fmt::Write) -> fmt::Result {
+///         for row in table.title().into_iter().chain(table.rows().iter().map(|r| &r[..])) {
+///             writeln!(f, "{}", row.join(","))?;
+///         }
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait Renderer {
+    fn render(&self, table: &Table, f: &mut dyn fmt::Write) -> fmt::Result;
+}
+
+/// The default plain text format with wrapped cells.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TextRenderer;
+
+impl Renderer for TextRenderer {
+    fn render(&self, table: &Table, f: &mut dyn fmt::Write) -> fmt::Result {
+        render_text(table, Selection::default(), false, f)
+    }
+}
+
+/// Like `TextRenderer` but without any escape sequences.
+///
+/// Escape sequences are stripped from the cells before measuring and no
+/// styles are applied.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PlainRenderer;
+
+impl Renderer for PlainRenderer {
+    fn render(&self, table: &Table, f: &mut dyn fmt::Write) -> fmt::Result {
+        render_text(table, Selection::default(), true, f)
+    }
+}
+
+/// Body rows and columns to render.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Selection<'s> {
+    /// Indices of the body rows, all rows if `None`.
+    pub rows: Option<&'s [usize]>,
+    /// Indices of the columns, all columns if `None`.
+    pub columns: Option<&'s [usize]>,
+}
+
+impl<'s> Selection<'s> {
+    fn is_all(&self) -> bool {
+        self.rows.is_none() && self.columns.is_none()
+    }
+}
+
+pub(crate) fn render_text(
+    table: &Table,
+    selection: Selection,
+    plain: bool,
+    f: &mut dyn fmt::Write,
+) -> fmt::Result {
+    let config = &table.config;
+    if !config.trim_line_ends && config.line_ending == LineEnding::Lf && config.final_line_ending {
+        return fmt_table(table, selection, plain, f);
+    }
+    let mut lines = LineEnds::new(table, f);
+    fmt_table(table, selection, plain, &mut lines)?;
+    if config.final_line_ending {
+        lines.flush_line_ending()?;
+    }
+    Ok(())
+}
+
+/// Write the selected part of `table`, stacked column chunks one by one.
+fn fmt_table(
+    table: &Table,
+    selection: Selection,
+    plain: bool,
+    f: &mut dyn fmt::Write,
+) -> fmt::Result {
+    let indices: Vec<usize> = match selection.rows {
+        Some(rows) => rows.to_vec(),
+        None => (0..table.rows.len()).collect(),
+    };
+    if indices.is_empty() && table.config.empty == Empty::Nothing {
+        return Ok(());
+    }
+    // Without records tables look the same in both layouts, see `Empty`.
+    if table.config.layout == Layout::Record && !indices.is_empty() {
+        return render_records(table, selection, &indices, plain, f);
+    }
+    if table.config.stack_columns && selection.columns.is_none() {
+        if let Some(chunks) = column_chunks(table, &indices) {
+            for (n, chunk) in chunks.iter().enumerate() {
+                if n > 0 {
+                    f.write_char('\n')?;
+                }
+                let chunk = Selection {
+                    rows: selection.rows,
+                    columns: Some(chunk),
+                };
+                fmt_table(table, chunk, plain, f)?;
+            }
+            return Ok(());
+        }
+    }
+    let placeholder = match table.config.empty {
+        _ if !indices.is_empty() => None,
+        Empty::Nothing | Empty::Title => None,
+        Empty::Placeholder(text) => Some(text),
+    };
+    let columns = table.display_columns(selection.columns);
+    let guides = table.tree_guides();
+    let mut rows: Vec<_> = indices
+        .iter()
+        .map(|&i| table.display_row(i, &columns, &guides))
+        .collect();
+    table.suppress_repeats(&mut rows, &columns);
+    let mut title = table.display_title(&columns);
+    let mut footer = table.display_footer(&columns);
+    if plain {
+        for cell in rows
+            .iter_mut()
+            .chain(title.iter_mut())
+            .chain(footer.iter_mut())
+            .flat_map(|r| r.iter_mut())
+        {
+            if let Cow::Owned(stripped) = strip_ansi(cell) {
+                *cell = Cow::Owned(stripped);
+            }
+        }
+    }
+    let prepared = if plain || !selection.is_all() {
+        None
+    } else {
+        table.prepared()
+    };
+    let mut dimensions = match prepared {
+        Some(prepared) => prepared.dimensions.clone(),
+        None => table.dimensions(title.as_deref(), footer.as_deref(), &rows, &columns),
+    };
+    if let Some(text) = placeholder.filter(|_| dimensions.is_empty()) {
+        dimensions.push(display_width(text));
+    }
+    let colored = !plain && table.colored();
+    for (row, &i) in rows.iter_mut().zip(&indices) {
+        for ((cell, column), &width) in row.iter_mut().zip(&columns).zip(&dimensions) {
+            if let Some(dynamic) = column.and_then(|c| table.cell_dynamic(i, c)) {
+                let content = dynamic.render(width, colored);
+                // Cells of nested tables may have escape sequences.
+                *cell = Cow::Owned(if plain {
+                    strip_ansi(&content).into_owned()
+                } else {
+                    content
+                });
+            }
+        }
+    }
+    if let Some(ellipsis) = table.config.truncate {
+        let markers: Vec<_> = columns.iter().map(|&c| table.display_marker(c)).collect();
+        for row in rows
+            .iter_mut()
+            .chain(title.iter_mut())
+            .chain(footer.iter_mut())
+        {
+            truncate_row(row, &dimensions, ellipsis, &markers);
+        }
+    }
+    let border = &table.config.border;
+    let framed = table.config.frame;
+    let h = border.horizontal;
+    let aligns: Vec<_> = columns
+        .iter()
+        .map(|&c| table.display_alignment(c))
+        .collect();
+    let outer = framed || table.config.outer_rules;
+    if outer {
+        let (left, right) = if framed {
+            (border.top_left, border.top_right)
+        } else {
+            ("", "")
+        };
+        fmt_rule(
+            table,
+            &dimensions,
+            (left, h, border.top_junction, right),
+            None,
+            f,
+        )?;
+    }
+    if let Some(ref title) = title {
+        let aligns: Vec<_> = if table.config.center_title {
+            vec![Alignment::Center; columns.len()]
+        } else {
+            aligns.clone()
+        };
+        let marks = cell_marks(table, title, &columns, colored);
+        let lines = wrap(title, &dimensions, &table.config);
+        let drawing = Drawing {
+            aligns: &aligns,
+            ..Drawing::default()
+        };
+        fmt_row(table, &lines, &dimensions, &marks, &drawing, f)?;
+        let chars = table.config.title_rule_chars.unwrap_or(RuleChars {
+            left: border.left_junction,
+            horizontal: table.config.title_rule.unwrap_or(h),
+            cross: border.cross,
+            right: border.right_junction,
+        });
+        let (left, right) = if framed {
+            (chars.left, chars.right)
+        } else {
+            ("", "")
+        };
+        let style = table.config.title_rule_style.filter(|_| colored);
+        fmt_rule(
+            table,
+            &dimensions,
+            (left, chars.horizontal, chars.cross, right),
+            style,
+            f,
+        )?;
+    }
+    let mut dividers = dividers(table, &indices);
+    let heat_ranges = if colored { table.heat_ranges() } else { vec![] };
+    for (row, &i) in rows.iter().zip(&indices) {
+        fmt_dividers(table, &mut dividers, i, &dimensions, plain, f)?;
+        let styles = if colored {
+            let styles = table.styles(i, &heat_ranges);
+            columns
+                .iter()
+                .map(|c| c.and_then(|c| styles.get(c).cloned().flatten()))
+                .collect()
+        } else {
+            vec![]
+        };
+        let separators: Vec<_> = (0..columns.len())
+            .map(|pos| {
+                let borders = |pos: usize| columns[pos].and_then(|c| table.cell_borders(i, c));
+                // The cell right of a separator decides first.
+                pos.checked_sub(1)
+                    .and_then(|left| borders(pos).or_else(|| borders(left)))
+            })
+            .collect();
+        let groups = if table.config.merge_cells {
+            merge_groups(row, &columns)
+        } else {
+            None
+        };
+        if let Some(groups) = groups {
+            let cells: Vec<_> = groups.iter().map(|g| row[g.start].clone()).collect();
+            let merged_columns: Vec<_> = groups.iter().map(|g| columns[g.start]).collect();
+            let widths: Vec<_> = groups
+                .iter()
+                .map(|g| merged_width(table, &dimensions[g.clone()]))
+                .collect();
+            let styles: Vec<_> = groups
+                .iter()
+                .map(|g| styles.get(g.start).cloned().flatten())
+                .collect();
+            let aligns: Vec<_> = groups
+                .iter()
+                .map(|g| match g.len() {
+                    1 => table.display_cell_alignment(i, columns[g.start]),
+                    _ => Alignment::Center,
+                })
+                .collect();
+            let separators: Vec<_> = groups.iter().map(|g| separators[g.start]).collect();
+            let lines = table.wrap_cells(i, &cells, &merged_columns, &widths, &guides[i]);
+            let marks = cell_marks(table, &cells, &merged_columns, colored);
+            let drawing = Drawing {
+                styles: &styles,
+                aligns: &aligns,
+                separators: &separators,
+            };
+            fmt_row(table, &lines, &widths, &marks, &drawing, f)?;
+            continue;
+        }
+        let lines = match prepared {
+            // Truncated, dynamic and custom wrapped cells don't match the
+            // prepared lines.
+            Some(prepared)
+                if table.config.truncate.is_none()
+                    && !table.has_dynamic(i)
+                    && !table.has_wrapper() =>
+            {
+                prepared
+                    .wrap(i, row)
+                    .unwrap_or_else(|| table.wrap_cells(i, row, &columns, &dimensions, &guides[i]))
+            }
+            _ => table.wrap_cells(i, row, &columns, &dimensions, &guides[i]),
+        };
+        let marks = cell_marks(table, row, &columns, colored);
+        let aligns: Vec<_> = columns
+            .iter()
+            .map(|&c| table.display_cell_alignment(i, c))
+            .collect();
+        let drawing = Drawing {
+            styles: &styles,
+            aligns: &aligns,
+            separators: &separators,
+        };
+        fmt_row(table, &lines, &dimensions, &marks, &drawing, f)?;
+    }
+    if let Some(text) = placeholder {
+        fmt_spanning(table, text, None, &dimensions, plain, f)?;
+    }
+    // Dividers after the last row only belong to a selection ending there.
+    let end = table.rows.len();
+    if selection.rows.is_none() || indices.last().map(|i| i + 1) == Some(end) {
+        fmt_dividers(table, &mut dividers, end, &dimensions, plain, f)?;
+    }
+    if let Some(ref footer) = footer {
+        if table.config.separate_footer {
+            let line = table.config.footer_rule.unwrap_or(h);
+            let (left, right) = if framed {
+                (border.left_junction, border.right_junction)
+            } else {
+                ("", "")
+            };
+            fmt_rule(
+                table,
+                &dimensions,
+                (left, line, border.cross, right),
+                None,
+                f,
+            )?;
+        }
+        let marks = cell_marks(table, footer, &columns, colored);
+        let lines = wrap(footer, &dimensions, &table.config);
+        let drawing = Drawing {
+            aligns: &aligns,
+            ..Drawing::default()
+        };
+        fmt_row(table, &lines, &dimensions, &marks, &drawing, f)?;
+    }
+    if outer {
+        let (left, right) = if framed {
+            (border.bottom_left, border.bottom_right)
+        } else {
+            ("", "")
+        };
+        fmt_rule(
+            table,
+            &dimensions,
+            (left, h, border.bottom_junction, right),
+            None,
+            f,
+        )?;
+    }
+    Ok(())
+}
+
+/// Write each of the body rows `indices` as a block of `header | value`
+/// lines.
+fn render_records(
+    table: &Table,
+    selection: Selection,
+    indices: &[usize],
+    plain: bool,
+    f: &mut dyn fmt::Write,
+) -> fmt::Result {
+    let rtl = table.config.right_to_left;
+    // The record line shows the row number.
+    let mut columns: Vec<_> = table
+        .display_columns(selection.columns)
+        .into_iter()
+        .filter(Option::is_some)
+        .collect();
+    // Records always list the columns top to bottom, the header is on the
+    // right instead.
+    if rtl {
+        columns.reverse();
+    }
+    let (key, value) = if rtl { (1, 0) } else { (0, 1) };
+    let title = table.display_title(&columns).unwrap_or_default();
+    let guides = table.tree_guides();
+    let mut records: Vec<Vec<Vec<Cow<str>>>> = indices
+        .iter()
+        .map(|&i| {
+            table
+                .display_row(i, &columns, &guides)
+                .into_iter()
+                .enumerate()
+                .map(|(k, value)| {
+                    let mut pair = vec![title.get(k).cloned().unwrap_or_default(), value];
+                    if plain {
+                        for cell in pair.iter_mut() {
+                            if let Cow::Owned(stripped) = strip_ansi(cell) {
+                                *cell = Cow::Owned(stripped);
+                            }
+                        }
+                    }
+                    if rtl {
+                        pair.reverse();
+                    }
+                    pair
+                })
+                .collect()
+        })
+        .collect();
+    let border = &table.config.border;
+    let framed = table.config.frame;
+    // Headers keep their width, values are wrapped to the rest.
+    let mut dimensions = vec![0, 0];
+    for pair in records.iter().flatten() {
+        for (dim, cell) in dimensions.iter_mut().zip(pair) {
+            *dim = cmp::max(*dim, cell_width(cell));
+        }
+    }
+    let rest = table
+        .width()
+        .saturating_sub(table.config.frame_width() + inner_width(table, &[dimensions[key], 0]));
+    dimensions[value] = match table.config.arrangement {
+        ContentArrangement::Disabled => dimensions[value],
+        // The headers don't wrap, there's nothing to balance.
+        ContentArrangement::Dynamic | ContentArrangement::MinimizeHeight => {
+            cmp::min(dimensions[value], rest)
+        }
+        ContentArrangement::DynamicFullWidth => rest,
+    };
+    let colored = !plain && table.colored();
+    let h = border.horizontal;
+    let label = |i: usize| format!("{}[ RECORD {} ]{}", h, i + 1, h);
+    // Widen the values so the longest record line fits.
+    let longest = indices
+        .iter()
+        .max()
+        .map_or(0, |&i| display_width(&label(i)));
+    dimensions[value] += longest.saturating_sub(inner_width(table, &dimensions));
+    let width = inner_width(table, &dimensions);
+    if let Some(ellipsis) = table.config.truncate {
+        for (pair, column) in records.iter_mut().flat_map(|r| r.iter_mut().zip(&columns)) {
+            let mut markers = [table.display_marker(None), table.display_marker(*column)];
+            if rtl {
+                markers.reverse();
+            }
+            truncate_row(pair, &dimensions, ellipsis, &markers);
+        }
+    }
+    let mut dividers = dividers(table, indices);
+    let heat_ranges = if colored { table.heat_ranges() } else { vec![] };
+    for (n, (record, &i)) in records.iter().zip(indices).enumerate() {
+        fmt_dividers(table, &mut dividers, i, &dimensions, plain, f)?;
+        let (left, right) = match (framed, n) {
+            (false, _) => ("", ""),
+            (true, 0) => (border.top_left, border.top_right),
+            (true, _) => (border.left_junction, border.right_junction),
+        };
+        let label = label(i);
+        f.write_str(left)?;
+        f.write_str(&label)?;
+        repeat(h, width - display_width(&label), f)?;
+        f.write_str(right)?;
+        f.write_char('\n')?;
+        let styles = if colored {
+            table.styles(i, &heat_ranges)
+        } else {
+            vec![]
+        };
+        for (pair, column) in record.iter().zip(&columns) {
+            let mut styles = [None, column.and_then(|c| styles.get(c).cloned().flatten())];
+            let mut pair_columns = [None, *column];
+            if rtl {
+                styles.reverse();
+                pair_columns.reverse();
+            }
+            let marks = cell_marks(table, pair, &pair_columns, colored);
+            let lines = table.wrap_cells(i, pair, &pair_columns, &dimensions, &guides[i]);
+            let drawing = Drawing {
+                styles: &styles,
+                aligns: &[Alignment::Left; 2],
+                separators: &[],
+            };
+            fmt_row(table, &lines, &dimensions, &marks, &drawing, f)?;
+        }
+    }
+    let end = table.rows.len();
+    if selection.rows.is_none() || indices.last().map(|i| i + 1) == Some(end) {
+        fmt_dividers(table, &mut dividers, end, &dimensions, plain, f)?;
+    }
+    // The first record line is the top line.
+    if (framed || table.config.outer_rules) && !records.is_empty() {
+        let (left, right) = if framed {
+            (border.bottom_left, border.bottom_right)
+        } else {
+            ("", "")
+        };
+        f.write_str(left)?;
+        repeat(h, width, f)?;
+        f.write_str(right)?;
+        f.write_char('\n')?;
+    }
+    Ok(())
+}
+
+/// Truncate the cells of `row` to their column's width.
+fn truncate_row(row: &mut [Cow<str>], dimensions: &[usize], ellipsis: Ellipsis, markers: &[&str]) {
+    for ((cell, width), marker) in row.iter_mut().zip(dimensions).zip(markers) {
+        if let Cow::Owned(short) = truncate(cell, *width, ellipsis, marker) {
+            *cell = Cow::Owned(short);
+        }
+    }
+}
+
+/// Split the columns into chunks fitting the width, each starting with the
+/// key column.
+///
+/// `None` if all columns fit.
+fn column_chunks(table: &Table, rows: &[usize]) -> Option<Vec<Vec<usize>>> {
+    let config = &table.config;
+    let columns = table.display_columns(None);
+    let mut widths = vec![0; columns.len()];
+    let title = table.display_title(&columns);
+    let footer = table.display_footer(&columns);
+    let guides = table.tree_guides();
+    for row in title.into_iter().chain(footer).chain(
+        rows.iter()
+            .map(|&i| table.display_row(i, &columns, &guides)),
+    ) {
+        for (width, cell) in widths.iter_mut().zip(&row) {
+            *width = cmp::max(*width, cell_width(cell));
+        }
+    }
+    let mut fixed = 0;
+    let mut rest = vec![];
+    for (column, width) in columns.iter().zip(widths) {
+        let width = column
+            .and_then(|c| table.columns.get(c)?.width)
+            .unwrap_or(width);
+        let width = width + config.padding * 2 + display_width(config.border.vertical);
+        match *column {
+            Some(c) if config.key_column != Some(c) => rest.push((c, width)),
+            _ => fixed += width,
+        }
+    }
+    // Every column was counted with a separator and padding on the right,
+    // the last column has neither.
+    let available = (table.width() + config.separator_width() + config.padding)
+        .saturating_sub(last_padding(table) + config.frame_width());
+    let key: Vec<usize> = config
+        .key_column
+        .into_iter()
+        .filter(|c| columns.contains(&Some(*c)))
+        .collect();
+    if config.right_to_left {
+        // The first chunk still gets the first columns.
+        rest.reverse();
+    }
+    let mut chunks: Vec<Vec<usize>> = vec![];
+    let mut used = available;
+    for (column, width) in rest {
+        if used + width > available && !matches!(chunks.last(), Some(c) if c.len() <= key.len()) {
+            chunks.push(key.clone());
+            used = fixed;
+        }
+        if let Some(chunk) = chunks.last_mut() {
+            chunk.push(column);
+        }
+        used += width;
+    }
+    if chunks.len() > 1 {
+        Some(chunks)
+    } else {
+        None
+    }
+}
+
+/// The dividers of `table` to write between the body rows `indices`.
+///
+/// Dividers are left out if the rows are out of order, like in sorted
+/// views, they wouldn't be between the rows they were added between.
+fn dividers<'t, 'a>(
+    table: &'t Table<'a>,
+    indices: &[usize],
+) -> Peekable<slice::Iter<'t, (usize, Divider<'a>)>> {
+    let ordered = indices.windows(2).all(|pair| pair[0] < pair[1]);
+    let dividers = if ordered { &table.dividers[..] } else { &[] };
+    dividers.iter().peekable()
+}
+
+/// Write the dividers preceding body row `i`.
+///
+/// Only the dividers right before `i` are written, earlier ones belong to
+/// rows which aren't rendered.
+fn fmt_dividers(
+    table: &Table,
+    dividers: &mut Peekable<slice::Iter<(usize, Divider)>>,
+    i: usize,
+    dimensions: &[usize],
+    plain: bool,
+    f: &mut dyn fmt::Write,
+) -> fmt::Result {
+    let mut gap = vec![];
+    while let Some(divider) = dividers.next_if(|d| d.0 <= i) {
+        gap.push(divider);
+    }
+    let last = gap.last().map(|d| d.0);
+    for (pos, divider) in gap {
+        if Some(*pos) == last {
+            fmt_divider(table, divider, dimensions, plain, f)?;
+        }
+    }
+    Ok(())
+}
+
+/// How the cells of a row are drawn, missing entries take the defaults.
+#[derive(Default)]
+struct Drawing<'s> {
+    styles: &'s [Option<Style>],
+    aligns: &'s [Alignment],
+    /// Overrides of the separators in front of the cells.
+    separators: &'s [Option<CellBorders<'s>>],
+}
+
+/// Write a row given the wrapped lines of its cells.
+fn fmt_row(
+    table: &Table,
+    expanded: &[Vec<Line>],
+    dimensions: &[usize],
+    marks: &[Marks],
+    drawing: &Drawing,
+    f: &mut dyn fmt::Write,
+) -> fmt::Result {
+    // Rows of empty cells still take a line.
+    let height = expanded
+        .iter()
+        .map(|x| cmp::max(x.len(), 1))
+        .max()
+        .unwrap_or(0);
+    let config = &table.config;
+    let edge = if config.frame {
+        config.border.vertical
+    } else {
+        ""
+    };
+    for i in 0..height {
+        f.write_str(edge)?;
+        for (col, (lines, width)) in expanded.iter().zip(dimensions).enumerate() {
+            if col > 0 {
+                match drawing.separators.get(col).cloned().flatten() {
+                    Some(CellBorders::Hidden) => spaces(display_width(config.border.vertical), f)?,
+                    Some(CellBorders::Vertical(vertical)) => f.write_str(vertical)?,
+                    None => f.write_str(config.border.vertical)?,
+                }
+            }
+            let (line, start) = lines.get(i).map_or(("", None), |l| (&*l.text, l.start));
+            let style = drawing.styles.get(col).cloned().unwrap_or_default();
+            let align = drawing.aligns.get(col).cloned().unwrap_or_default();
+            let mut marks = match (marks.get(col), start) {
+                (Some(marks), Some(start)) => marks.for_line(start..start + line.len()),
+                _ => vec![],
+            };
+            let line = match config.continuation {
+                Some(Continuation::Suffix(marker)) if i + 1 < lines.len() => {
+                    Cow::Owned(format!("{}{}", line, marker))
+                }
+                Some(Continuation::Prefix(marker)) if i > 0 && i < lines.len() => {
+                    for (range, _) in &mut marks {
+                        *range = range.start + marker.len()..range.end + marker.len();
+                    }
+                    Cow::Owned(format!("{}{}", marker, line))
+                }
+                _ => Cow::Borrowed(line),
+            };
+            let right = if col + 1 == dimensions.len() {
+                right_padding(table)
+            } else {
+                Some(config.padding)
+            };
+            let padding = (config.padding, right);
+            fmt_cell(&line, *width, padding, align, style, &marks, f)?;
+        }
+        f.write_str(edge)?;
+        f.write_char('\n')?;
+    }
+    Ok(())
+}
+
+/// Write a line spanning all columns.
+fn fmt_divider(
+    table: &Table,
+    divider: &Divider,
+    dimensions: &[usize],
+    plain: bool,
+    f: &mut dyn fmt::Write,
+) -> fmt::Result {
+    let config = &table.config;
+    let edge = if config.frame {
+        config.border.vertical
+    } else {
+        ""
+    };
+    let width = inner_width(table, dimensions);
+    match *divider {
+        Divider::Spacer => {
+            f.write_str(edge)?;
+            spaces(width, f)?;
+            f.write_str(edge)?;
+            f.write_char('\n')
+        }
+        Divider::Section(ref title) => {
+            let style = table.section_style.filter(|_| table.colored());
+            fmt_spanning(table, title, style, dimensions, plain, f)
+        }
+    }
+}
+
+/// Write `text` in a single cell spanning all columns.
+fn fmt_spanning(
+    table: &Table,
+    text: &str,
+    mut style: Option<Style>,
+    dimensions: &[usize],
+    plain: bool,
+    f: &mut dyn fmt::Write,
+) -> fmt::Result {
+    let config = &table.config;
+    let edge = if config.frame {
+        config.border.vertical
+    } else {
+        ""
+    };
+    let mut text = table.sanitize(Cow::Borrowed(text));
+    if plain {
+        if let Cow::Owned(stripped) = strip_ansi(&text) {
+            text = Cow::Owned(stripped);
+        }
+        style = None;
+    }
+    let width = inner_width(table, dimensions);
+    let width = width.saturating_sub(config.padding + last_padding(table));
+    let padding = (config.padding, right_padding(table));
+    for line in config.wrapping.split(&text, width) {
+        f.write_str(edge)?;
+        fmt_cell(&text[line], width, padding, Alignment::Left, style, &[], f)?;
+        f.write_str(edge)?;
+        f.write_char('\n')?;
+    }
+    Ok(())
+}
+
+/// The ranges of adjacent data cells of `row` in `columns` with the same
+/// content, `None` if no cells are merged.
+fn merge_groups(row: &[Cow<str>], columns: &[Option<usize>]) -> Option<Vec<Range<usize>>> {
+    // The row numbers aren't merged.
+    let data = |pos: usize| columns.get(pos).is_some_and(Option::is_some);
+    let mut groups: Vec<Range<usize>> = vec![];
+    for (pos, cell) in row.iter().enumerate() {
+        match groups.last_mut() {
+            Some(group)
+                if !cell.is_empty()
+                    && data(pos)
+                    && data(group.start)
+                    && row[group.start] == *cell =>
+            {
+                group.end = pos + 1
+            }
+            _ => groups.push(pos..pos + 1),
+        }
+    }
+    if groups.len() < row.len() {
+        Some(groups)
+    } else {
+        None
+    }
+}
+
+/// The width of a cell spanning columns of `dimensions`, including the
+/// padding and separators between them.
+fn merged_width(table: &Table, dimensions: &[usize]) -> usize {
+    let config = &table.config;
+    let between = 2 * config.padding + display_width(config.border.vertical);
+    dimensions.iter().sum::<usize>() + dimensions.len().saturating_sub(1) * between
+}
+
+/// The width of a row without the frame.
+fn inner_width(table: &Table, dimensions: &[usize]) -> usize {
+    let config = &table.config;
+    let separators = dimensions.len().saturating_sub(1) * display_width(config.border.vertical);
+    let padding = match dimensions.len() {
+        0 => 0,
+        n => config.padding * (2 * n - 1) + last_padding(table),
+    };
+    dimensions.iter().sum::<usize>() + padding + separators
+}
+
+/// The padding on the right of the last column.
+fn last_padding(table: &Table) -> usize {
+    if table.config.pad_last_column {
+        table.config.padding
+    } else {
+        0
+    }
+}
+
+/// Spaces after the content of the last column, `None` for none at all.
+fn right_padding(table: &Table) -> Option<usize> {
+    if table.config.pad_last_column || table.config.frame {
+        Some(last_padding(table))
+    } else {
+        None
+    }
+}
+
+/// Write a horizontal line given its `(left, horizontal, cross, right)`
+/// characters.
+fn fmt_rule(
+    table: &Table,
+    dimensions: &[usize],
+    (left, horizontal, cross, right): (&str, &str, &str, &str),
+    style: Option<Style>,
+    f: &mut dyn fmt::Write,
+) -> fmt::Result {
+    let config = &table.config;
+    if horizontal.is_empty() {
+        // Borderless tables have no rules.
+        return Ok(());
+    }
+    if let Some(style) = style {
+        write!(f, "{}", style.prefix())?;
+    }
+    f.write_str(left)?;
+    for (col, dim) in dimensions.iter().enumerate() {
+        if col > 0 {
+            f.write_str(cross)?;
+        }
+        let right = if col + 1 == dimensions.len() {
+            last_padding(table)
+        } else {
+            config.padding
+        };
+        repeat(horizontal, dim + config.padding + right, f)?;
+    }
+    f.write_str(right)?;
+    if let Some(style) = style {
+        f.write_str(style.suffix())?;
+    }
+    f.write_char('\n')
+}
+
+/// Highlighted ranges of a cell.
+struct Marks {
+    ranges: Vec<(Range<usize>, Style)>,
+}
+
+impl Marks {
+    /// The ranges within the wrapped line at `line` of the cell, relative
+    /// to the line.
+    fn for_line(&self, line: Range<usize>) -> Vec<(Range<usize>, Style)> {
+        let Range { start, end } = line;
+        self.ranges
+            .iter()
+            .filter(|(r, _)| r.start < end && r.end > start)
+            .map(|(r, style)| (r.start.max(start) - start..r.end.min(end) - start, *style))
+            .collect()
+    }
+}
+
+/// The highlighted ranges of each cell in `row` displayed in `columns`.
+fn cell_marks<S: AsRef<str>>(
+    table: &Table,
+    row: &[S],
+    columns: &[Option<usize>],
+    colored: bool,
+) -> Vec<Marks> {
+    if !colored || table.highlights.is_empty() {
+        return vec![];
+    }
+    row.iter()
+        .zip(columns)
+        .map(|(cell, column)| Marks {
+            ranges: match *column {
+                Some(_) => marks(cell.as_ref(), &table.highlights),
+                None => vec![],
+            },
+        })
+        .collect()
+}
+
+/// Pad `cell` to `width` according to `align`, styling only the content.
+///
+/// `padding` is the padding on the left and right, nothing at all is written
+/// after the content if the right one is `None`. `marks` are ranges of
+/// `cell` styled differently.
+fn fmt_cell(
+    cell: &str,
+    width: usize,
+    (padding, right_padding): (usize, Option<usize>),
+    align: Alignment,
+    style: Option<Style>,
+    marks: &[(Range<usize>, Style)],
+    f: &mut dyn fmt::Write,
+) -> fmt::Result {
+    let fill = width.saturating_sub(display_width(cell));
+    let (left, right) = match align {
+        Alignment::Left => (0, fill),
+        Alignment::Right => (fill, 0),
+        Alignment::Center => (fill / 2, fill - fill / 2),
+    };
+    // The background covers the padding too.
+    let background = style.and_then(|s| s.background());
+    spaces_styled(padding + left, background, f)?;
+    let mut pos = 0;
+    for (range, mark) in marks {
+        styled(&cell[pos..range.start], style, f)?;
+        styled(&cell[range.clone()], Some(*mark), f)?;
+        pos = range.end;
+    }
+    styled(&cell[pos..], style, f)?;
+    match right_padding {
+        Some(padding) => spaces_styled(right + padding, background, f),
+        None => Ok(()),
+    }
+}
+
+/// Write `n` spaces with `background`.
+fn spaces_styled(n: usize, background: Option<Style>, f: &mut dyn fmt::Write) -> fmt::Result {
+    match background {
+        Some(style) if n > 0 => {
+            write!(f, "{}", style.prefix())?;
+            spaces(n, f)?;
+            f.write_str(style.suffix())
+        }
+        _ => spaces(n, f),
+    }
+}
+
+/// Write `s` with `style`.
+fn styled(s: &str, style: Option<Style>, f: &mut dyn fmt::Write) -> fmt::Result {
+    match style {
+        Some(style) if !s.is_empty() => write!(f, "{}{}{}", style.prefix(), s, style.suffix()),
+        _ => f.write_str(s),
+    }
+}
+
+/// A writer applying the line end settings to the `\n`s written to it.
+struct LineEnds<'w> {
+    inner: &'w mut dyn fmt::Write,
+    /// Drop spaces at the end of lines.
+    trim: bool,
+    ending: &'static str,
+    /// Spaces written but held back until more content follows.
+    spaces: usize,
+    /// Whether a line ending is held back until more content follows.
+    pending: bool,
+}
+
+impl<'w> LineEnds<'w> {
+    fn new(table: &Table, inner: &'w mut dyn fmt::Write) -> LineEnds<'w> {
+        LineEnds {
+            inner,
+            trim: table.config.trim_line_ends,
+            ending: table.config.line_ending.as_str(),
+            spaces: 0,
+            pending: false,
+        }
+    }
+
+    fn flush_line_ending(&mut self) -> fmt::Result {
+        if self.pending {
+            self.pending = false;
+            self.inner.write_str(self.ending)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'w> fmt::Write for LineEnds<'w> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for (i, line) in s.split('\n').enumerate() {
+            if i > 0 {
+                self.spaces = 0;
+                self.flush_line_ending()?;
+                self.pending = true;
+            }
+            let content = if self.trim {
+                line.trim_end_matches(' ')
+            } else {
+                line
+            };
+            if !content.is_empty() {
+                self.flush_line_ending()?;
+                spaces(self.spaces, self.inner)?;
+                self.inner.write_str(content)?;
+                self.spaces = 0;
+            }
+            self.spaces += line.len() - content.len();
+        }
+        Ok(())
+    }
+}
+
+/// A writer measuring the lines written to it instead of keeping them.
+#[derive(Default)]
+pub(crate) struct Measure {
+    width: usize,
+    height: usize,
+    /// The width of the unfinished line.
+    line: usize,
+}
+
+impl Measure {
+    /// The width of the widest line and the number of lines.
+    pub fn size(mut self) -> (usize, usize) {
+        if self.line > 0 {
+            self.end_line();
+        }
+        (self.width, self.height)
+    }
+
+    fn end_line(&mut self) {
+        self.width = cmp::max(self.width, self.line);
+        self.height += 1;
+        self.line = 0;
+    }
+}
+
+impl fmt::Write for Measure {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for (i, part) in s.split('\n').enumerate() {
+            if i > 0 {
+                self.end_line();
+            }
+            self.line += display_width(part.trim_end_matches('\r'));
+        }
+        Ok(())
+    }
+}
+
+const SPACES: &str = "                                ";
+
+/// Write `n` spaces.
+fn spaces(mut n: usize, f: &mut dyn fmt::Write) -> fmt::Result {
+    while n > 0 {
+        let chunk = cmp::min(n, SPACES.len());
+        f.write_str(&SPACES[..chunk])?;
+        n -= chunk;
+    }
+    Ok(())
+}
+
+/// Write `s` `n` times.
+fn repeat(s: &str, n: usize, f: &mut dyn fmt::Write) -> fmt::Result {
+    for _ in 0..n {
+        f.write_str(s)?;
+    }
+    Ok(())
+}
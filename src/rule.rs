@@ -0,0 +1,132 @@
+//! Conditional formatting evaluated at render time.
+use Style;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Condition<'a> {
+    Always,
+    Gt(f64),
+    Ge(f64),
+    Lt(f64),
+    Le(f64),
+    Eq(&'a str),
+    Contains(&'a str),
+}
+
+/// Styles the body cells of a column matching a condition.
+///
+/// Numeric conditions only match cells that parse as a number. If several
+/// rules match a cell the one added last wins.
+///
+/// ```
+/// use lazytable::{Rule, Style, Table};
+///
+/// let mut table = Table::default();
+/// table.add_rule(Rule::column(3).gt(90.0).style(Style::red().bold()));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rule<'a> {
+    column: usize,
+    condition: Condition<'a>,
+    style: Style,
+}
+
+impl<'a> Rule<'a> {
+    /// A rule for column `idx` matching every cell.
+    pub fn column(idx: usize) -> Rule<'a> {
+        Rule {
+            column: idx,
+            condition: Condition::Always,
+            style: Style::default(),
+        }
+    }
+
+    /// Match cells greater than `value`.
+    pub fn gt(self, value: f64) -> Rule<'a> {
+        self.when(Condition::Gt(value))
+    }
+
+    /// Match cells greater than or equal to `value`.
+    pub fn ge(self, value: f64) -> Rule<'a> {
+        self.when(Condition::Ge(value))
+    }
+
+    /// Match cells less than `value`.
+    pub fn lt(self, value: f64) -> Rule<'a> {
+        self.when(Condition::Lt(value))
+    }
+
+    /// Match cells less than or equal to `value`.
+    pub fn le(self, value: f64) -> Rule<'a> {
+        self.when(Condition::Le(value))
+    }
+
+    /// Match cells equal to `value`.
+    pub fn eq(self, value: &'a str) -> Rule<'a> {
+        self.when(Condition::Eq(value))
+    }
+
+    /// Match cells containing `value`.
+    pub fn contains(self, value: &'a str) -> Rule<'a> {
+        self.when(Condition::Contains(value))
+    }
+
+    /// The style of matching cells.
+    pub fn style(mut self, style: Style) -> Rule<'a> {
+        self.style = style;
+        self
+    }
+
+    fn when(mut self, condition: Condition<'a>) -> Rule<'a> {
+        self.condition = condition;
+        self
+    }
+
+    /// Follow the column after columns were reordered, `order` holds the
+    /// previous index of each column.
+    pub(crate) fn reorder(&mut self, order: &[usize]) {
+        if let Some(idx) = order.iter().position(|&c| c == self.column) {
+            self.column = idx;
+        }
+    }
+
+    /// The style for `cell` in column `idx` if the rule matches.
+    pub(crate) fn eval(&self, idx: usize, cell: &str) -> Option<Style> {
+        if idx == self.column && self.matches(cell) {
+            Some(self.style)
+        } else {
+            None
+        }
+    }
+
+    fn matches(&self, cell: &str) -> bool {
+        let number = || cell.trim().parse::<f64>().ok();
+        match self.condition {
+            Condition::Always => true,
+            Condition::Gt(v) => number().is_some_and(|n| n > v),
+            Condition::Ge(v) => number().is_some_and(|n| n >= v),
+            Condition::Lt(v) => number().is_some_and(|n| n < v),
+            Condition::Le(v) => number().is_some_and(|n| n <= v),
+            Condition::Eq(v) => cell == v,
+            Condition::Contains(v) => cell.contains(v),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eval() {
+        let rule = Rule::column(1).gt(90.0).style(Style::red());
+        assert_eq!(rule.eval(1, "91"), Some(Style::red()));
+        assert_eq!(rule.eval(1, " 90.5 "), Some(Style::red()));
+        assert_eq!(rule.eval(1, "90"), None);
+        assert_eq!(rule.eval(1, "high"), None);
+        assert_eq!(rule.eval(0, "91"), None);
+        let rule = Rule::column(0).contains("ERR");
+        assert_eq!(rule.eval(0, "ERROR"), Some(Style::default()));
+        assert_eq!(Rule::column(0).eq("a").eval(0, "ab"), None);
+        assert_eq!(Rule::column(0).le(1.0).eval(0, "1"), Some(Style::default()));
+    }
+}
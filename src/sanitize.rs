@@ -0,0 +1,101 @@
+//! Handling of control characters in cell content.
+use std::borrow::Cow;
+
+use style::strip_ansi;
+
+/// What to do with control characters like `\r`, bell or escape in cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ControlChars {
+    /// Leave them alone, they may corrupt the output.
+    #[default]
+    PassThrough,
+    /// Remove them.
+    Strip,
+    /// Replace them with their Unicode control pictures like `␍` or `␇`.
+    Replace,
+}
+
+impl ControlChars {
+    /// Apply this mode to `cell`.
+    pub fn apply<'c>(&self, cell: &'c str) -> Cow<'c, str> {
+        if *self == ControlChars::PassThrough || !cell.chars().any(char::is_control) {
+            return Cow::Borrowed(cell);
+        }
+        let sanitized = match *self {
+            ControlChars::PassThrough => unreachable!(),
+            ControlChars::Strip => cell.chars().filter(|c| !c.is_control()).collect(),
+            ControlChars::Replace => cell.chars().map(control_picture).collect(),
+        };
+        Cow::Owned(sanitized)
+    }
+}
+
+/// `cell` made safe to print, see `TableConfig::sanitized`.
+pub(crate) fn untrusted(cell: &str, max_len: usize) -> Cow<'_, str> {
+    let clean = |c: &char| !c.is_control() && !is_invisible(*c);
+    let long = cell.chars().nth(max_len).is_some();
+    if !long && cell.chars().all(|c| clean(&c) && !is_odd_space(c)) {
+        return Cow::Borrowed(cell);
+    }
+    let stripped = strip_ansi(cell);
+    let mut chars = stripped
+        .chars()
+        .filter(clean)
+        .map(|c| if is_odd_space(c) { ' ' } else { c });
+    let mut sanitized: String = chars.by_ref().take(max_len).collect();
+    if chars.next().is_some() && sanitized.pop().is_some() {
+        sanitized.push('…');
+    }
+    Cow::Owned(sanitized)
+}
+
+/// Zero width and bidirectional formatting characters which can hide or
+/// reorder text.
+fn is_invisible(c: char) -> bool {
+    matches!(c as u32,
+        0x00ad | 0x034f | 0x061c | 0x115f | 0x1160 | 0x17b4 | 0x17b5 | 0x180e
+        | 0x200b..=0x200f | 0x202a..=0x202e | 0x2060..=0x206f | 0x3164 | 0xfeff
+        | 0xfe00..=0xfe0f | 0xffa0 | 0xfff9..=0xfffb | 0xe0000..=0xe0fff)
+}
+
+/// Spaces other than `' '` which may be mistaken for it.
+fn is_odd_space(c: char) -> bool {
+    c != ' ' && c.is_whitespace()
+}
+
+fn control_picture(c: char) -> char {
+    match c as u32 {
+        n @ 0x00..=0x1f => ::std::char::from_u32(0x2400 + n).unwrap_or('\u{fffd}'),
+        0x7f => '\u{2421}',
+        _ if c.is_control() => '\u{fffd}',
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let cell = "a\rb\x07c\x1b[0m\u{85}";
+        assert_eq!(ControlChars::PassThrough.apply(cell), cell);
+        assert_eq!(ControlChars::Strip.apply(cell), "abc[0m");
+        assert_eq!(ControlChars::Replace.apply(cell), "a␍b␇c␛[0m\u{fffd}");
+        assert_eq!(ControlChars::Replace.apply("\x7f"), "␡");
+        assert!(matches!(
+            ControlChars::Strip.apply("plain"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_untrusted() {
+        assert!(matches!(untrusted("plain", 5), Cow::Borrowed(_)));
+        assert_eq!(untrusted("\x1b[31mred\x1b[0m\r\n", 10), "red");
+        assert_eq!(untrusted("a\u{202e}b\u{200b}c\u{a0}d", 10), "abc d");
+        assert_eq!(untrusted("abcdef", 4), "abc…");
+        assert_eq!(untrusted("abcdef", 0), "");
+        assert_eq!(untrusted("ab\x07", 2), "ab");
+    }
+}
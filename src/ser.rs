@@ -0,0 +1,512 @@
+//! Serializing sequences of structs or maps into a table.
+//!
+//! ```
+//! #[macro_use]
+//! extern crate serde_derive;
+//! extern crate lazytable;
+//!
+//! #[derive(Serialize)]
+//! struct Host {
+//!     name: &'static str,
+//!     up: bool,
+//! }
+//!
+//! fn main() {
+//!     let hosts = [Host { name: "db", up: false }, Host { name: "web", up: true }];
+//!     let table = lazytable::ser::to_table(&hosts).unwrap();
+//!     assert_eq!(
+//!         table.to_string(),
+//!         " name | up    \n------+-------\n db   | false \n web  | true  \n"
+//!     );
+//! }
+//! ```
+use std::borrow::Cow;
+use std::error;
+use std::fmt;
+
+use serde::ser::{self, Impossible, Serialize};
+
+use Table;
+
+/// Serialize `value`, a sequence of flat structs or maps, into a table.
+///
+/// Field names make up the title in the order they are first seen, rows
+/// leave cells of missing fields empty.
+pub fn to_table<T: Serialize + ?Sized>(value: &T) -> Result<Table<'static>, Error> {
+    let mut serializer = TableSerializer::new();
+    value.serialize(&mut serializer)?;
+    Ok(serializer.into_table())
+}
+
+/// Why a value can't be serialized into a table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Error {
+        Error(msg.to_string())
+    }
+}
+
+/// Collects serialized rows, see `to_table`.
+#[derive(Debug, Default)]
+pub struct TableSerializer {
+    title: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl TableSerializer {
+    pub fn new() -> TableSerializer {
+        TableSerializer::default()
+    }
+
+    /// The rows serialized so far, titled with their field names.
+    pub fn into_table(self) -> Table<'static> {
+        let mut table = Table::default();
+        if !self.title.is_empty() {
+            table.set_title(self.title);
+        }
+        table.extend_rows(
+            self.rows
+                .into_iter()
+                .map(|row| row.into_iter().map(Cow::Owned).collect()),
+        );
+        table
+    }
+
+    /// The index of the column named `key`, appending it if it's new.
+    fn column(&mut self, key: String) -> usize {
+        match self.title.iter().position(|k| *k == key) {
+            Some(idx) => idx,
+            None => {
+                self.title.push(key);
+                self.title.len() - 1
+            }
+        }
+    }
+}
+
+/// Serializer methods of scalars all failing with `$msg`.
+macro_rules! reject_scalars {
+    ($msg:expr) => {
+        reject_scalars!($msg;
+            serialize_bool: bool, serialize_i8: i8, serialize_i16: i16,
+            serialize_i32: i32, serialize_i64: i64, serialize_u8: u8,
+            serialize_u16: u16, serialize_u32: u32, serialize_u64: u64,
+            serialize_f32: f32, serialize_f64: f64, serialize_char: char,
+            serialize_str: &str, serialize_bytes: &[u8]);
+
+        fn serialize_none(self) -> Result<Self::Ok, Error> {
+            Err(Error($msg.to_owned()))
+        }
+
+        fn serialize_unit(self) -> Result<Self::Ok, Error> {
+            Err(Error($msg.to_owned()))
+        }
+
+        fn serialize_unit_struct(self, _: &'static str) -> Result<Self::Ok, Error> {
+            Err(Error($msg.to_owned()))
+        }
+
+        fn serialize_unit_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+        ) -> Result<Self::Ok, Error> {
+            Err(Error($msg.to_owned()))
+        }
+
+        fn serialize_newtype_variant<T: Serialize + ?Sized>(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: &T,
+        ) -> Result<Self::Ok, Error> {
+            Err(Error($msg.to_owned()))
+        }
+
+        fn serialize_tuple_struct(
+            self,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeTupleStruct, Error> {
+            Err(Error($msg.to_owned()))
+        }
+
+        fn serialize_tuple_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeTupleVariant, Error> {
+            Err(Error($msg.to_owned()))
+        }
+
+        fn serialize_struct_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeStructVariant, Error> {
+            Err(Error($msg.to_owned()))
+        }
+    };
+    ($msg:expr; $($method:ident: $ty:ty),*) => {
+        $(
+            fn $method(self, _: $ty) -> Result<Self::Ok, Error> {
+                Err(Error($msg.to_owned()))
+            }
+        )*
+    };
+}
+
+const NOT_A_SEQUENCE: &str = "expected a sequence of rows";
+const NOT_A_ROW: &str = "expected rows to be structs or maps";
+
+impl ser::Serializer for &mut TableSerializer {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Impossible<(), Error>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    reject_scalars!(NOT_A_SEQUENCE);
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Error(NOT_A_SEQUENCE.to_owned()))
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct, Error> {
+        Err(Error(NOT_A_SEQUENCE.to_owned()))
+    }
+}
+
+impl ser::SerializeSeq for &mut TableSerializer {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(RowSerializer { table: self })
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for &mut TableSerializer {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Serializes a single row.
+struct RowSerializer<'t> {
+    table: &'t mut TableSerializer,
+}
+
+impl<'t> ser::Serializer for RowSerializer<'t> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Fields<'t>;
+    type SerializeStruct = Fields<'t>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    reject_scalars!(NOT_A_ROW);
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(Error(NOT_A_ROW.to_owned()))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(Error(NOT_A_ROW.to_owned()))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Fields<'t>, Error> {
+        Ok(Fields {
+            table: self.table,
+            row: vec![],
+            column: None,
+        })
+    }
+
+    fn serialize_struct(self, _: &'static str, len: usize) -> Result<Fields<'t>, Error> {
+        self.serialize_map(Some(len))
+    }
+}
+
+/// Serializes the fields of a row into their columns.
+struct Fields<'t> {
+    table: &'t mut TableSerializer,
+    row: Vec<String>,
+    /// The column of the value following a map key.
+    column: Option<usize>,
+}
+
+impl<'t> Fields<'t> {
+    fn set(&mut self, column: usize, cell: String) {
+        if self.row.len() <= column {
+            self.row.resize(column + 1, String::new());
+        }
+        self.row[column] = cell;
+    }
+}
+
+impl<'t> ser::SerializeMap for Fields<'t> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        let key = key.serialize(CellSerializer)?;
+        self.column = Some(self.table.column(key));
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let column = self
+            .column
+            .take()
+            .ok_or_else(|| Error("value without key".to_owned()))?;
+        let cell = value.serialize(CellSerializer)?;
+        self.set(column, cell);
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.table.rows.push(self.row);
+        Ok(())
+    }
+}
+
+impl<'t> ser::SerializeStruct for Fields<'t> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let column = self.table.column(key.to_owned());
+        let cell = value.serialize(CellSerializer)?;
+        self.set(column, cell);
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+/// Serializes a scalar into the text of a cell.
+struct CellSerializer;
+
+/// Serializer methods of scalars returning them as displayed.
+macro_rules! display_scalars {
+    ($($method:ident: $ty:ty),*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<String, Error> {
+                Ok(v.to_string())
+            }
+        )*
+    };
+}
+
+const NOT_A_CELL: &str = "expected a scalar cell value";
+
+impl ser::Serializer for CellSerializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    display_scalars!(
+        serialize_bool: bool, serialize_i8: i8, serialize_i16: i16,
+        serialize_i32: i32, serialize_i64: i64, serialize_i128: i128,
+        serialize_u8: u8, serialize_u16: u16, serialize_u32: u32,
+        serialize_u64: u64, serialize_u128: u128, serialize_f32: f32,
+        serialize_f64: f64, serialize_char: char, serialize_str: &str
+    );
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<String, Error> {
+        Ok(String::from_utf8_lossy(v).into_owned())
+    }
+
+    fn serialize_none(self) -> Result<String, Error> {
+        Ok(String::new())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> {
+        Ok(String::new())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<String, Error> {
+        Ok(String::new())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.to_owned())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(Error(NOT_A_CELL.to_owned()))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(Error(NOT_A_CELL.to_owned()))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(Error(NOT_A_CELL.to_owned()))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error(NOT_A_CELL.to_owned()))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Error(NOT_A_CELL.to_owned()))
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct, Error> {
+        Err(Error(NOT_A_CELL.to_owned()))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error(NOT_A_CELL.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_maps() {
+        let mut a = BTreeMap::new();
+        a.insert("name", Some("a"));
+        let mut b = BTreeMap::new();
+        b.insert("size", Some("2"));
+        b.insert("name", None);
+        let table = to_table(&vec![a, b]).unwrap();
+        assert_eq!(table.title().unwrap(), ["name", "size"]);
+        assert_eq!(table.rows(), [vec!["a"], vec!["", "2"]]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(to_table(&1).err().unwrap().to_string(), NOT_A_SEQUENCE);
+        assert_eq!(to_table(&[1]).err().unwrap().to_string(), NOT_A_ROW);
+        let mut nested = BTreeMap::new();
+        nested.insert("a", vec![1]);
+        assert_eq!(to_table(&[nested]).err().unwrap().to_string(), NOT_A_CELL);
+    }
+}
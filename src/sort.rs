@@ -0,0 +1,102 @@
+//! Comparators for sorting rows.
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use {Order, Row};
+
+/// Stable sort of the `indices` of `rows` by the columns `keys`.
+pub(crate) fn sort_indices<F>(
+    rows: &[Row],
+    indices: &mut [usize],
+    keys: &[(usize, Order)],
+    compare: F,
+) where
+    F: Fn(&str, &str) -> Ordering,
+{
+    let cell = |i: usize, col: usize| rows[i].get(col).map_or("", |c| c.as_ref());
+    indices.sort_by(|&a, &b| {
+        keys.iter()
+            .map(|&(col, order)| {
+                let ordering = compare(cell(a, col), cell(b, col));
+                match order {
+                    Order::Ascending => ordering,
+                    Order::Descending => ordering.reverse(),
+                }
+            })
+            .find(|o| *o != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+/// Compare strings the way humans do, with numbers by their value.
+///
+/// Runs of ASCII digits are compared by their value and everything else
+/// character by character, so `item2` comes before `item10`. Signs and
+/// decimal points are ordinary characters.
+///
+/// ```
+/// use lazytable::natural_cmp;
+///
+/// let mut items = vec!["item10", "item2", "item1"];
+/// items.sort_by(|a, b| natural_cmp(a, b));
+/// assert_eq!(items, ["item1", "item2", "item10"]);
+/// ```
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        match (a.peek().cloned(), b.peek().cloned()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (x, y) = (digits(&mut a), digits(&mut b));
+                let (xs, ys) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                let ordering = xs
+                    .len()
+                    .cmp(&ys.len())
+                    .then_with(|| xs.cmp(ys))
+                    // Fewer leading zeros first.
+                    .then_with(|| x.len().cmp(&y.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+/// Take the run of ASCII digits at the start of `chars`.
+fn digits(chars: &mut Peekable<Chars>) -> String {
+    let mut run = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        run.push(c);
+    }
+    run
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_natural_cmp() {
+        let mut items = vec![
+            "item10", "item2", "b", "item1", "a10b", "a2b", "10", "9", "item02",
+        ];
+        items.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(
+            items,
+            ["9", "10", "a2b", "a10b", "b", "item1", "item2", "item02", "item10"]
+        );
+        assert_eq!(natural_cmp("x", "x"), Ordering::Equal);
+        assert_eq!(natural_cmp("x", "x1"), Ordering::Less);
+    }
+}
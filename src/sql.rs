@@ -0,0 +1,87 @@
+//! SQL statements.
+use std::fmt;
+
+use style::strip_ansi;
+use {Renderer, Table};
+
+/// An `INSERT INTO` statement per body row.
+///
+/// The title names the columns, rows are cut or filled with `NULL` to its
+/// length. Without title all columns are inserted in order. `Cell::none`
+/// cells become `NULL`, all other cells are string literals. Escape sequences
+/// are stripped from the cells.
+#[derive(Clone, Copy, Debug)]
+pub struct SqlRenderer<'n> {
+    table_name: &'n str,
+}
+
+impl<'n> SqlRenderer<'n> {
+    /// Statements inserting into the table `table_name`.
+    pub fn new(table_name: &'n str) -> SqlRenderer<'n> {
+        SqlRenderer { table_name }
+    }
+}
+
+impl<'n> Renderer for SqlRenderer<'n> {
+    fn render(&self, table: &Table, f: &mut dyn fmt::Write) -> fmt::Result {
+        let title = table.title();
+        let columns = title.map_or_else(|| table.column_count(), |t| t.len());
+        for (i, row) in table.formatted_rows().iter().enumerate() {
+            write!(f, "INSERT INTO {}", Identifier(self.table_name))?;
+            if let Some(title) = title {
+                f.write_str(" (")?;
+                for (idx, name) in title.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", Identifier(&strip_ansi(name)))?;
+                }
+                f.write_char(')')?;
+            }
+            f.write_str(" VALUES (")?;
+            for idx in 0..columns {
+                if idx > 0 {
+                    f.write_str(", ")?;
+                }
+                match row.get(idx) {
+                    Some(cell) if !table.is_none(i, idx) => {
+                        write!(f, "'{}'", strip_ansi(cell).replace('\'', "''"))?
+                    }
+                    _ => f.write_str("NULL")?,
+                }
+            }
+            f.write_str(");\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// A double quoted identifier.
+struct Identifier<'s>(&'s str);
+
+impl<'s> fmt::Display for Identifier<'s> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\"", self.0.replace('"', "\"\""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Cell;
+
+    #[test]
+    fn test_sql_inserts() {
+        let mut table = Table::default();
+        table.set_title(vec!["who", "say \"what\""]);
+        table.add_row(vec!["o'brien", "tea"]);
+        table.add_cells(vec![Cell::new("bob"), Cell::none()]);
+        table.add_row(vec!["eve"]);
+        assert_eq!(
+            table.to_sql_inserts("drinks"),
+            "INSERT INTO \"drinks\" (\"who\", \"say \"\"what\"\"\") VALUES ('o''brien', 'tea');\n\
+             INSERT INTO \"drinks\" (\"who\", \"say \"\"what\"\"\") VALUES ('bob', NULL);\n\
+             INSERT INTO \"drinks\" (\"who\", \"say \"\"what\"\"\") VALUES ('eve', NULL);\n"
+        );
+    }
+}
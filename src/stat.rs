@@ -0,0 +1,85 @@
+//! Statistics over numeric columns.
+
+/// A statistic computed by `Table::summary_row`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stat {
+    Min,
+    Max,
+    Sum,
+    Mean,
+    /// The number of numeric cells.
+    Count,
+}
+
+impl Stat {
+    /// The label of this statistic in summary cells.
+    pub fn label(self) -> &'static str {
+        match self {
+            Stat::Min => "min",
+            Stat::Max => "max",
+            Stat::Sum => "sum",
+            Stat::Mean => "mean",
+            Stat::Count => "count",
+        }
+    }
+}
+
+/// `stats` of `cells` joined by `", "`, `None` unless all non-empty cells
+/// are numbers and there is at least one.
+pub(crate) fn summarize<'c, I>(cells: I, stats: &[Stat]) -> Option<String>
+where
+    I: IntoIterator<Item = &'c str>,
+{
+    let mut numbers = vec![];
+    for cell in cells.into_iter().map(str::trim).filter(|c| !c.is_empty()) {
+        numbers.push((cell.parse::<f64>().ok().filter(|n| n.is_finite())?, cell));
+    }
+    let by_value = |a: &&(f64, &str), b: &&(f64, &str)| a.0.total_cmp(&b.0);
+    let min = numbers.iter().min_by(by_value)?.1;
+    let max = numbers.iter().max_by(by_value)?.1;
+    let sum: f64 = numbers.iter().map(|n| n.0).sum();
+    let summary: Vec<_> = stats
+        .iter()
+        .map(|&stat| {
+            let value = match stat {
+                Stat::Min => min.to_owned(),
+                Stat::Max => max.to_owned(),
+                Stat::Sum => round(sum),
+                Stat::Mean => round(sum / numbers.len() as f64),
+                Stat::Count => numbers.len().to_string(),
+            };
+            format!("{} {}", stat.label(), value)
+        })
+        .collect();
+    Some(summary.join(", "))
+}
+
+/// `n` with at most two decimals.
+fn round(n: f64) -> String {
+    let s = format!("{:.2}", n);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".to_owned()
+    } else {
+        s.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize() {
+        let all = [Stat::Min, Stat::Max, Stat::Sum, Stat::Mean, Stat::Count];
+        assert_eq!(
+            summarize(vec!["3", " 0.10", "", "-2"], &all).unwrap(),
+            "min -2, max 3, sum 1.1, mean 0.37, count 3"
+        );
+        assert_eq!(summarize(vec!["1", "x"], &all), None);
+        assert_eq!(summarize(vec!["", "NaN"], &all), None);
+        assert_eq!(summarize(Vec::new(), &all), None);
+        assert_eq!(round(-0.001), "0");
+        assert_eq!(round(100.0), "100");
+    }
+}
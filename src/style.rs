@@ -0,0 +1,258 @@
+//! ANSI styling of cell content.
+use std::borrow::Cow;
+use std::fmt;
+
+/// Terminal colors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    /// One of the 256 indexed colors.
+    Fixed(u8),
+    /// A 24 bit color.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    fn write_code(&self, base: u8, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Color::Black => write!(f, "{}", base),
+            Color::Red => write!(f, "{}", base + 1),
+            Color::Green => write!(f, "{}", base + 2),
+            Color::Yellow => write!(f, "{}", base + 3),
+            Color::Blue => write!(f, "{}", base + 4),
+            Color::Magenta => write!(f, "{}", base + 5),
+            Color::Cyan => write!(f, "{}", base + 6),
+            Color::White => write!(f, "{}", base + 7),
+            Color::Fixed(n) => write!(f, "{};5;{}", base + 8, n),
+            Color::Rgb(r, g, b) => write!(f, "{};2;{};{};{}", base + 8, r, g, b),
+        }
+    }
+}
+
+/// Foreground, background and text attributes of a cell.
+///
+/// ```
+/// use lazytable::{Color, Style};
+///
+/// let alert = Style::red().bold().on(Color::White);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: bool,
+    dimmed: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl Style {
+    /// A style without any effect.
+    pub fn new() -> Style {
+        Style::default()
+    }
+
+    /// A style with foreground `color`.
+    pub fn fg(color: Color) -> Style {
+        Style {
+            fg: Some(color),
+            ..Style::default()
+        }
+    }
+
+    pub fn black() -> Style {
+        Style::fg(Color::Black)
+    }
+
+    pub fn red() -> Style {
+        Style::fg(Color::Red)
+    }
+
+    pub fn green() -> Style {
+        Style::fg(Color::Green)
+    }
+
+    pub fn yellow() -> Style {
+        Style::fg(Color::Yellow)
+    }
+
+    pub fn blue() -> Style {
+        Style::fg(Color::Blue)
+    }
+
+    pub fn magenta() -> Style {
+        Style::fg(Color::Magenta)
+    }
+
+    pub fn cyan() -> Style {
+        Style::fg(Color::Cyan)
+    }
+
+    pub fn white() -> Style {
+        Style::fg(Color::White)
+    }
+
+    /// Set the background color.
+    pub fn on(mut self, color: Color) -> Style {
+        self.bg = Some(color);
+        self
+    }
+
+    pub fn bold(mut self) -> Style {
+        self.bold = true;
+        self
+    }
+
+    pub fn dimmed(mut self) -> Style {
+        self.dimmed = true;
+        self
+    }
+
+    pub fn italic(mut self) -> Style {
+        self.italic = true;
+        self
+    }
+
+    pub fn underline(mut self) -> Style {
+        self.underline = true;
+        self
+    }
+
+    /// Only the background of this style, `None` without one.
+    pub(crate) fn background(&self) -> Option<Style> {
+        self.bg.map(|bg| Style::new().on(bg))
+    }
+
+    /// `true` if this style wouldn't emit any escape sequence.
+    pub fn is_plain(&self) -> bool {
+        *self == Style::default()
+    }
+
+    /// The escape sequence switching to this style.
+    pub fn prefix(&self) -> Prefix {
+        Prefix(*self)
+    }
+
+    /// The escape sequence resetting this style.
+    pub fn suffix(&self) -> &'static str {
+        if self.is_plain() {
+            ""
+        } else {
+            RESET
+        }
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+
+/// Displays the escape sequence of a `Style`.
+pub struct Prefix(Style);
+
+impl fmt::Display for Prefix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let style = self.0;
+        if style.is_plain() {
+            return Ok(());
+        }
+        let mut codes = vec![];
+        if style.bold {
+            codes.push("1");
+        }
+        if style.dimmed {
+            codes.push("2");
+        }
+        if style.italic {
+            codes.push("3");
+        }
+        if style.underline {
+            codes.push("4");
+        }
+        write!(f, "\x1b[{}", codes.join(";"))?;
+        if let Some(fg) = style.fg {
+            if !codes.is_empty() {
+                write!(f, ";")?;
+            }
+            fg.write_code(30, f)?;
+        }
+        if let Some(bg) = style.bg {
+            if !codes.is_empty() || style.fg.is_some() {
+                write!(f, ";")?;
+            }
+            bg.write_code(40, f)?;
+        }
+        write!(f, "m")
+    }
+}
+
+/// Remove ANSI escape sequences from `s`.
+pub fn strip_ansi(s: &str) -> Cow<'_, str> {
+    if !s.contains('\x1b') {
+        return Cow::Borrowed(s);
+    }
+    let mut stripped = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters and intermediates up to a final byte.
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: terminated by BEL or ST (`ESC \`).
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Cow::Owned(stripped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("plain"), "plain");
+        assert_eq!(strip_ansi("\x1b[1;31mred\x1b[0m!"), "red!");
+        assert_eq!(strip_ansi("\x1b]8;;http://x\x1b\\link\x1b]8;;\x07"), "link");
+        assert_eq!(strip_ansi("a\x1b"), "a");
+    }
+
+    #[test]
+    fn test_prefix() {
+        assert_eq!(Style::new().prefix().to_string(), "");
+        assert_eq!(Style::red().prefix().to_string(), "\x1b[31m");
+        assert_eq!(
+            Style::red().bold().on(Color::Fixed(7)).prefix().to_string(),
+            "\x1b[1;31;48;5;7m"
+        );
+        assert_eq!(
+            Style::new().on(Color::Rgb(1, 2, 3)).prefix().to_string(),
+            "\x1b[48;2;1;2;3m"
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! Shortening cells to the width of their column.
+use std::borrow::Cow;
+
+use display_width;
+use style::strip_ansi;
+use unicode_width::UnicodeWidthChar;
+
+/// Which part of a cell is replaced by the marker when truncating.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Ellipsis {
+    /// Keep the start: `/home/user/proj…`.
+    #[default]
+    End,
+    /// Keep the start and the end: `/home/us…/debug/app`.
+    Middle,
+}
+
+/// `cell` shortened to at most `width` columns, `marker` marking where
+/// content was cut.
+///
+/// Escape sequences are dropped from cells which are shortened. If not even
+/// the marker fits the cell is cut without it.
+pub(crate) fn truncate<'c>(
+    cell: &'c str,
+    width: usize,
+    ellipsis: Ellipsis,
+    marker: &str,
+) -> Cow<'c, str> {
+    if display_width(cell) <= width {
+        return Cow::Borrowed(cell);
+    }
+    let cell = strip_ansi(cell);
+    let room = match width.checked_sub(display_width(marker)) {
+        Some(room) => room,
+        None => return Cow::Owned(head(&cell, width).to_owned()),
+    };
+    let short = match ellipsis {
+        Ellipsis::End => format!("{}{}", head(&cell, room), marker),
+        Ellipsis::Middle => {
+            let end = room / 2;
+            let start = room - end;
+            format!("{}{}{}", head(&cell, start), marker, tail(&cell, end))
+        }
+    };
+    Cow::Owned(short)
+}
+
+/// The longest start of `s` at most `width` columns wide.
+fn head(s: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, c) in s.char_indices() {
+        used += c.width().unwrap_or(0);
+        if used > width {
+            return &s[..i];
+        }
+    }
+    s
+}
+
+/// The longest end of `s` at most `width` columns wide.
+fn tail(s: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, c) in s.char_indices().rev() {
+        used += c.width().unwrap_or(0);
+        if used > width {
+            return &s[i + c.len_utf8()..];
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate() {
+        let path = "/home/user/target/debug/app";
+        let truncate = |cell, width, ellipsis| truncate(cell, width, ellipsis, "…");
+        assert_eq!(truncate(path, 30, Ellipsis::End), path);
+        assert_eq!(truncate(path, 12, Ellipsis::End), "/home/user/…");
+        assert_eq!(truncate(path, 12, Ellipsis::Middle), "/home/…g/app");
+        assert_eq!(truncate("日本語", 4, Ellipsis::End), "日…");
+        assert_eq!(truncate("\x1b[31mabcdef\x1b[0m", 4, Ellipsis::End), "abc…");
+        assert_eq!(truncate("abc", 0, Ellipsis::Middle), "");
+    }
+
+    #[test]
+    fn test_marker() {
+        let id = "0123456789";
+        assert_eq!(truncate(id, 8, Ellipsis::End, "..."), "01234...");
+        assert_eq!(truncate(id, 8, Ellipsis::Middle, "..."), "012...89");
+        assert_eq!(truncate(id, 2, Ellipsis::End, "..."), "01");
+    }
+}
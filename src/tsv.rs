@@ -0,0 +1,45 @@
+//! Tab separated values for pasting into spreadsheets.
+use std::borrow::Cow;
+use std::fmt;
+
+use style::strip_ansi;
+use {Renderer, Table};
+
+/// The title and the formatted body rows, one line each with cells
+/// separated by tabs.
+///
+/// Cells are never wrapped or padded and there are no borders. Tabs and line
+/// breaks in cells become spaces and escape sequences are stripped.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TsvRenderer;
+
+impl Renderer for TsvRenderer {
+    fn render(&self, table: &Table, f: &mut dyn fmt::Write) -> fmt::Result {
+        if let Some(title) = table.title() {
+            fmt_line(title, f)?;
+        }
+        for row in table.formatted_rows() {
+            fmt_line(&row, f)?;
+        }
+        Ok(())
+    }
+}
+
+fn fmt_line(row: &[Cow<str>], f: &mut dyn fmt::Write) -> fmt::Result {
+    for (idx, cell) in row.iter().enumerate() {
+        if idx > 0 {
+            f.write_char('\t')?;
+        }
+        let cell = strip_ansi(cell);
+        if cell.contains(['\t', '\n', '\r']) {
+            let cell: Vec<&str> = cell
+                .split(['\t', '\n', '\r'])
+                .filter(|s| !s.is_empty())
+                .collect();
+            f.write_str(&cell.join(" "))?;
+        } else {
+            f.write_str(&cell)?;
+        }
+    }
+    f.write_char('\n')
+}
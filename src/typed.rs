@@ -0,0 +1,213 @@
+//! Tables of typed rows.
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::Arc;
+
+use {Alignment, Order, Table, TableConfig};
+
+type DisplayFn<'a, T> = Box<dyn Fn(&T) -> String + Send + Sync + 'a>;
+type CompareFn<'a, T> = Box<dyn Fn(&T, &T, Order) -> Ordering + Send + Sync + 'a>;
+
+/// A column of a `TypedTable`, showing a value of each row.
+pub struct Column<'a, T> {
+    name: &'a str,
+    display: DisplayFn<'a, T>,
+    compare: CompareFn<'a, T>,
+    alignment: Option<Alignment>,
+}
+
+impl<'a, T> Column<'a, T> {
+    /// A column titled `name` showing the value `accessor` returns for a
+    /// row.
+    ///
+    /// Rows are sorted by these values. Values which can't be compared with
+    /// themselves, like `NaN`, sort last in either order.
+    pub fn new<F, V>(name: &'a str, accessor: F) -> Column<'a, T>
+    where
+        F: Fn(&T) -> V + Send + Sync + 'a,
+        V: fmt::Display + PartialOrd,
+    {
+        let accessor = Arc::new(accessor);
+        let display = Arc::clone(&accessor);
+        Column {
+            name,
+            display: Box::new(move |row| display(row).to_string()),
+            compare: Box::new(move |a, b, order| {
+                let (a, b) = (accessor(a), accessor(b));
+                match a.partial_cmp(&b) {
+                    Some(ordering) if order == Order::Descending => ordering.reverse(),
+                    Some(ordering) => ordering,
+                    None => unordered(&a).cmp(&unordered(&b)),
+                }
+            }),
+            alignment: None,
+        }
+    }
+
+    /// Align the cells of this column.
+    pub fn alignment(mut self, alignment: Alignment) -> Column<'a, T> {
+        self.alignment = Some(alignment);
+        self
+    }
+}
+
+/// Whether `value` can't be compared with itself, like `NaN`.
+fn unordered<V: PartialOrd>(value: &V) -> bool {
+    value.partial_cmp(value).is_none()
+}
+
+/// A table whose rows are values of `T`, shown by its columns.
+///
+/// ```
+/// use lazytable::typed::Column;
+/// use lazytable::{Alignment, Order, TableConfig, TypedTable};
+///
+/// struct Host {
+///     name: &'static str,
+///     load: f64,
+/// }
+///
+/// let mut table = TypedTable::new(
+///     TableConfig::default(),
+///     vec![
+///         Column::new("host", |h: &Host| h.name),
+///         Column::new("load", |h: &Host| h.load).alignment(Alignment::Right),
+///     ],
+/// );
+/// table.add_row(Host { name: "a", load: 9.5 });
+/// table.add_row(Host { name: "b", load: 10.0 });
+/// table.sort_by_columns(&[(1, Order::Descending)]);
+/// assert_eq!(table.rows()[0].name, "b");
+/// assert_eq!(
+///     table.to_string(),
+///     " host | load \n------+------\n b    |   10 \n a    |  9.5 \n"
+/// );
+/// ```
+pub struct TypedTable<'a, T> {
+    columns: Vec<Column<'a, T>>,
+    rows: Vec<T>,
+    table: Table<'a>,
+}
+
+impl<'a, T> TypedTable<'a, T> {
+    /// An empty table with `config` and `columns`.
+    pub fn new(config: TableConfig<'a>, columns: Vec<Column<'a, T>>) -> TypedTable<'a, T> {
+        TypedTable::with_table(Table::new(config), columns)
+    }
+
+    /// Like `new` with the settings of `table`, e.g. its column formats and
+    /// rules. Its title and body rows are replaced.
+    pub fn with_table(mut table: Table<'a>, columns: Vec<Column<'a, T>>) -> TypedTable<'a, T> {
+        table.clear_rows();
+        table.set_title(columns.iter().map(|c| c.name));
+        for (idx, column) in columns.iter().enumerate() {
+            if let Some(alignment) = column.alignment {
+                table.set_column_alignment(idx, alignment);
+            }
+        }
+        TypedTable {
+            columns,
+            rows: vec![],
+            table,
+        }
+    }
+
+    /// Add `row` below the rows added so far.
+    pub fn add_row(&mut self, row: T) {
+        let cells: Vec<String> = self.columns.iter().map(|c| (c.display)(&row)).collect();
+        self.table.add_row(cells);
+        self.rows.push(row);
+    }
+
+    /// Add all `rows` below the rows added so far.
+    pub fn add_rows<I: IntoIterator<Item = T>>(&mut self, rows: I) {
+        for row in rows {
+            self.add_row(row);
+        }
+    }
+
+    /// The rows in their current order.
+    pub fn rows(&self) -> &[T] {
+        &self.rows
+    }
+
+    /// Stable sort of the rows by the values of the columns `keys`, the
+    /// first key deciding first.
+    pub fn sort_by_columns(&mut self, keys: &[(usize, Order)]) {
+        let mut order: Vec<usize> = (0..self.rows.len()).collect();
+        {
+            let rows = &self.rows;
+            let columns = &self.columns;
+            order.sort_by(|&a, &b| {
+                keys.iter()
+                    .map(|&(col, order)| (columns[col].compare)(&rows[a], &rows[b], order))
+                    .find(|o| *o != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+        let mut rows: Vec<_> = self.rows.drain(..).map(Some).collect();
+        self.rows = order.iter().filter_map(|&i| rows[i].take()).collect();
+        self.table.select_rows(&order);
+    }
+
+    /// The table showing the rows.
+    pub fn table(&self) -> &Table<'a> {
+        &self.table
+    }
+
+    /// The table showing the rows, without the typed rows.
+    pub fn into_table(self) -> Table<'a> {
+        self.table
+    }
+}
+
+impl<'a, T> fmt::Display for TypedTable<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.table.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_typed() {
+        let mut table = TypedTable::new(
+            TableConfig::default(),
+            vec![
+                Column::new("n", |n: &(u32, f64)| n.0),
+                Column::new("x", |n: &(u32, f64)| n.1),
+            ],
+        );
+        table.add_rows(vec![(9, 1.0), (10, f64::NAN), (10, 0.5)]);
+        table.sort_by_columns(&[(0, Order::Descending), (1, Order::Ascending)]);
+        assert_eq!(table.rows()[0].0, 10);
+        assert_eq!(table.rows()[2], (9, 1.0));
+        let cells: Vec<_> = table.table().rows().iter().map(|r| &r[0]).collect();
+        assert_eq!(cells, ["10", "10", "9"]);
+    }
+
+    #[test]
+    fn test_sort_nan_last() {
+        let mut table =
+            TypedTable::new(TableConfig::default(), vec![Column::new("x", |x: &f64| *x)]);
+        table.add_rows((0..50).map(|i| {
+            if i % 7 == 0 {
+                f64::NAN
+            } else {
+                f64::from(i % 11)
+            }
+        }));
+        for &order in &[Order::Ascending, Order::Descending] {
+            table.sort_by_columns(&[(0, order)]);
+            let rows = table.rows();
+            assert!(rows[..42].iter().all(|x| !x.is_nan()));
+            assert!(rows[42..].iter().all(|x| x.is_nan()));
+            assert!(rows[..42].windows(2).all(|w| match order {
+                Order::Ascending => w[0] <= w[1],
+                Order::Descending => w[0] >= w[1],
+            }));
+        }
+    }
+}
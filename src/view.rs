@@ -0,0 +1,188 @@
+//! Differently shaped renders of one table.
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+
+use render::{render_text, Selection};
+use sort::sort_indices;
+use {Order, Table};
+
+type FilterFn<'t> = Box<dyn Fn(&[Cow<str>]) -> bool + 't>;
+type CompareFn<'t> = Box<dyn Fn(&str, &str) -> Ordering + 't>;
+
+/// A borrowed table with filtered, sorted and hidden rows and columns.
+///
+/// None of this touches the table, so several views can render the same
+/// data. Filters are applied first, then the sort and then the range.
+/// Spacers and sections are only shown while the rows keep their order.
+///
+/// ```
+/// use lazytable::{Order, Table};
+///
+/// let mut table = Table::default();
+/// table.set_title(["host", "state", "load"]);
+/// table.add_row(["a", "up", "0.5"]);
+/// table.add_row(["b", "down", "0.0"]);
+/// table.add_row(["c", "up", "0.9"]);
+///
+/// let busy = table
+///     .view()
+///     .filter(|row| row[1] == "up")
+///     .sort_by_columns(&[(2, Order::Descending)])
+///     .hide_column(1)
+///     .range(0, 1);
+/// assert_eq!(busy.to_string(), " host | load \n------+------\n c    | 0.9  \n");
+/// ```
+pub struct TableView<'t, 'a: 't> {
+    table: &'t Table<'a>,
+    filters: Vec<FilterFn<'t>>,
+    sort: Vec<(usize, Order)>,
+    compare: Option<CompareFn<'t>>,
+    hidden: Vec<usize>,
+    range: Option<(usize, usize)>,
+}
+
+impl<'t, 'a: 't> TableView<'t, 'a> {
+    /// A view showing all of `table`.
+    pub fn new(table: &'t Table<'a>) -> TableView<'t, 'a> {
+        TableView {
+            table,
+            filters: vec![],
+            sort: vec![],
+            compare: None,
+            hidden: vec![],
+            range: None,
+        }
+    }
+
+    /// Only show body rows for which `f` returns `true`.
+    ///
+    /// Several filters must all match.
+    pub fn filter<F>(mut self, f: F) -> TableView<'t, 'a>
+    where
+        F: Fn(&[Cow<str>]) -> bool + 't,
+    {
+        self.filters.push(Box::new(f));
+        self
+    }
+
+    /// Sort body rows like `Table::sort_by_columns`.
+    pub fn sort_by_columns(mut self, keys: &[(usize, Order)]) -> TableView<'t, 'a> {
+        self.sort = keys.to_vec();
+        self.compare = None;
+        self
+    }
+
+    /// Sort body rows like `Table::sort_by_columns_with`.
+    pub fn sort_by_columns_with<F>(
+        mut self,
+        keys: &[(usize, Order)],
+        compare: F,
+    ) -> TableView<'t, 'a>
+    where
+        F: Fn(&str, &str) -> Ordering + 't,
+    {
+        self.sort = keys.to_vec();
+        self.compare = Some(Box::new(compare));
+        self
+    }
+
+    /// Don't show column `idx`.
+    pub fn hide_column(mut self, idx: usize) -> TableView<'t, 'a> {
+        self.hidden.push(idx);
+        self
+    }
+
+    /// Show at most `limit` of the remaining rows starting at `offset`.
+    pub fn range(mut self, offset: usize, limit: usize) -> TableView<'t, 'a> {
+        self.range = Some((offset, limit));
+        self
+    }
+
+    /// The indices of the shown body rows in the table.
+    pub fn row_indices(&self) -> Vec<usize> {
+        let rows = &self.table.rows;
+        let mut indices: Vec<usize> = (0..rows.len())
+            .filter(|&i| self.filters.iter().all(|f| f(&rows[i])))
+            .collect();
+        if !self.sort.is_empty() {
+            match self.compare {
+                Some(ref compare) => sort_indices(rows, &mut indices, &self.sort, compare),
+                None => sort_indices(rows, &mut indices, &self.sort, |a, b| a.cmp(b)),
+            }
+        }
+        if let Some((offset, limit)) = self.range {
+            indices = indices.into_iter().skip(offset).take(limit).collect();
+        }
+        indices
+    }
+
+    /// The indices of the shown columns in the table.
+    pub fn column_indices(&self) -> Vec<usize> {
+        (0..self.table.column_count())
+            .filter(|c| !self.hidden.contains(c))
+            .collect()
+    }
+}
+
+impl<'t, 'a: 't> fmt::Display for TableView<'t, 'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rows = self.row_indices();
+        let columns = self.column_indices();
+        let selection = Selection {
+            rows: Some(&rows),
+            columns: Some(&columns),
+        };
+        render_text(self.table, selection, false, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {natural_cmp, Style, TableConfig};
+
+    #[test]
+    fn test_view() {
+        let mut table = Table::new(TableConfig::default().show_row_numbers(true));
+        table.set_title(["name", "size"]);
+        table.add_row(["a", "10"]);
+        table.add_section("more");
+        table.add_row(["b", "9"]);
+        table.add_row(["c", "100"]);
+        table.set_column_width(1, 6);
+        let view = table
+            .view()
+            .filter(|row| row[0] != "a")
+            .sort_by_columns_with(&[(1, Order::Ascending)], natural_cmp);
+        assert_eq!(view.row_indices(), vec![1, 2]);
+        let should = " # | name | size   \n---+------+--------\n more              \n 2 | b    | 9      \n 3 | c    | 100    \n";
+        assert_eq!(view.to_string(), should);
+        let view = table.view().sort_by_columns(&[(0, Order::Descending)]);
+        assert_eq!(view.row_indices(), vec![2, 1, 0]);
+        let should = " # | name | size   \n---+------+--------\n 3 | c    | 100    \n 2 | b    | 9      \n 1 | a    | 10     \n";
+        assert_eq!(view.to_string(), should);
+        let view = table.view().hide_column(0).range(1, 5);
+        assert_eq!(view.column_indices(), vec![1]);
+        assert_eq!(view.row_indices(), vec![1, 2]);
+        let should = " # | size   \n---+--------\n more       \n 2 | 9      \n 3 | 100    \n";
+        assert_eq!(view.to_string(), should);
+    }
+
+    #[test]
+    fn test_view_styles() {
+        let mut table = Table::default();
+        table.add_row(["a", "b"]);
+        table.set_row_style_fn(|_, row| {
+            if row[1] == "b" {
+                Some(Style::red())
+            } else {
+                None
+            }
+        });
+        table.highlight("a", Style::blue());
+        table.force_color(true);
+        let view = table.view().hide_column(0);
+        assert_eq!(view.to_string(), " \x1b[31mb\x1b[0m \n");
+    }
+}
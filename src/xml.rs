@@ -0,0 +1,70 @@
+//! XML reports.
+use std::fmt;
+
+use style::strip_ansi;
+use {Renderer, Table};
+
+/// XML like `<table><row><cell name="who">alice</cell></row></table>`.
+///
+/// Cells are named by the title cell of their column, cells of untitled
+/// columns have no name. Escape sequences are stripped from the cells.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct XmlRenderer;
+
+impl Renderer for XmlRenderer {
+    fn render(&self, table: &Table, f: &mut dyn fmt::Write) -> fmt::Result {
+        let title = table.title().unwrap_or(&[]);
+        f.write_str("<table>")?;
+        for row in table.formatted_rows() {
+            f.write_str("<row>")?;
+            for (idx, cell) in row.iter().enumerate() {
+                match title.get(idx) {
+                    Some(name) => write!(f, "<cell name=\"{}\">", Escaped(&strip_ansi(name)))?,
+                    None => f.write_str("<cell>")?,
+                }
+                write!(f, "{}</cell>", Escaped(&strip_ansi(cell)))?;
+            }
+            f.write_str("</row>")?;
+        }
+        f.write_str("</table>")
+    }
+}
+
+/// Text with XML markup characters escaped and characters XML doesn't
+/// allow, like most control characters, replaced by `U+FFFD`.
+struct Escaped<'s>(&'s str);
+
+impl<'s> fmt::Display for Escaped<'s> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&apos;")?,
+                '\t' | '\n' | '\r' => fmt::Write::write_char(f, c)?,
+                '\0'..='\x1f' | '\u{fffe}' | '\u{ffff}' => f.write_str("\u{fffd}")?,
+                c => fmt::Write::write_char(f, c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escaped() {
+        assert_eq!(
+            Escaped("<a href=\"x\">Tom & Jerry's</a>").to_string(),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&apos;s&lt;/a&gt;"
+        );
+        assert_eq!(
+            Escaped("a\0b\x07\t\r\n\u{ffff}").to_string(),
+            "a\u{fffd}b\u{fffd}\t\r\n\u{fffd}"
+        );
+    }
+}
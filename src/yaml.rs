@@ -0,0 +1,142 @@
+//! YAML reports.
+use std::fmt;
+
+use style::strip_ansi;
+use {Renderer, Table};
+
+/// YAML, a sequence with a mapping per body row keyed by the title.
+///
+/// Cells of untitled columns are keyed by their column index. Cells are
+/// quoted unless they read back as the same plain scalar, decimal numbers
+/// like `-1.5` are left plain so they load as numbers. Other numbers of the
+/// YAML core schema, like `0x1F` or `1e3`, are quoted. Escape sequences are
+/// stripped from the cells.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct YamlRenderer;
+
+impl Renderer for YamlRenderer {
+    fn render(&self, table: &Table, f: &mut dyn fmt::Write) -> fmt::Result {
+        let rows = table.formatted_rows();
+        if rows.is_empty() {
+            return f.write_str("[]\n");
+        }
+        let title = table.title().unwrap_or(&[]);
+        for row in &rows {
+            if row.is_empty() {
+                f.write_str("- {}\n")?;
+                continue;
+            }
+            for (idx, cell) in row.iter().enumerate() {
+                f.write_str(if idx == 0 { "- " } else { "  " })?;
+                match title.get(idx) {
+                    Some(name) => fmt_scalar(&strip_ansi(name), f)?,
+                    None => write!(f, "{}", idx)?,
+                }
+                f.write_str(": ")?;
+                fmt_scalar(&strip_ansi(cell), f)?;
+                f.write_char('\n')?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write `s` plain if it reads back as a string or number, double quoted
+/// otherwise.
+fn fmt_scalar(s: &str, f: &mut dyn fmt::Write) -> fmt::Result {
+    if is_plain(s) {
+        return f.write_str(s);
+    }
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\x{:02x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// Whether `s` can be written as a plain scalar.
+fn is_plain(s: &str) -> bool {
+    const INDICATORS: &str = "-?:,[]{}#&*!|>'\"%@`";
+    const RESERVED: [&str; 12] = [
+        "~", "null", "true", "false", "yes", "no", "on", "off", "y", "n", ".nan", ".inf",
+    ];
+    let first = match s.chars().next() {
+        Some(c) => c,
+        None => return false,
+    };
+    let is_number = s.parse::<f64>().is_ok()
+        && s.bytes()
+            .all(|b| b.is_ascii_digit() || b == b'.' || b == b'-');
+    (is_number || (!INDICATORS.contains(first) && !is_core_number(s)))
+        && s.trim() == s
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && !s.ends_with(':')
+        && !s.chars().any(char::is_control)
+        && !RESERVED.iter().any(|r| r.eq_ignore_ascii_case(s))
+}
+
+/// Whether `s` loads as an int or float with the YAML core schema.
+fn is_core_number(s: &str) -> bool {
+    let digits = |s: &str, radix: u32| !s.is_empty() && s.chars().all(|c| c.is_digit(radix));
+    if let Some(hex) = s.strip_prefix("0x") {
+        return digits(hex, 16);
+    }
+    if let Some(octal) = s.strip_prefix("0o") {
+        return digits(octal, 8);
+    }
+    let unsigned = s.strip_prefix(['-', '+']).unwrap_or(s);
+    if [".inf", ".Inf", ".INF"].contains(&unsigned) || [".nan", ".NaN", ".NAN"].contains(&s) {
+        return true;
+    }
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(i) => (&unsigned[..i], Some(&unsigned[i + 1..])),
+        None => (unsigned, None),
+    };
+    let exponent = match exponent {
+        Some(e) => digits(e.strip_prefix(['-', '+']).unwrap_or(e), 10),
+        None => true,
+    };
+    let mantissa = match mantissa.split_once('.') {
+        Some(("", fraction)) => digits(fraction, 10),
+        Some((int, fraction)) => digits(int, 10) && (fraction.is_empty() || digits(fraction, 10)),
+        None => digits(mantissa, 10),
+    };
+    mantissa && exponent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scalar(s: &str) -> String {
+        let mut out = String::new();
+        fmt_scalar(s, &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_scalar() {
+        assert_eq!(scalar("alice"), "alice");
+        assert_eq!(scalar("two words"), "two words");
+        assert_eq!(scalar("-1.5"), "-1.5");
+        assert_eq!(scalar(""), "\"\"");
+        assert_eq!(scalar("yes"), "\"yes\"");
+        assert_eq!(scalar("- item"), "\"- item\"");
+        assert_eq!(scalar("key: value"), "\"key: value\"");
+        assert_eq!(scalar(" padded"), "\" padded\"");
+        assert_eq!(scalar("say \"hi\"\n"), "\"say \\\"hi\\\"\\n\"");
+        assert_eq!(scalar(".5"), ".5");
+        for number in ["0x1F", "0o17", "1e3", "+1", "-.inf", "1.5E-2"] {
+            assert_eq!(scalar(number), format!("\"{}\"", number));
+        }
+        assert_eq!(scalar("1-2"), "1-2");
+    }
+}